@@ -2,7 +2,7 @@ use podstru_derive::Builder;
 use podstru_internal::Builder;
 use std::fmt::Debug;
 
-#[derive(Builder, Debug)]
+#[derive(Builder, Debug, PartialEq)]
 struct Data {
   pub field0: usize,
   pub field1: f32,
@@ -12,5 +12,13 @@ struct Data {
 
 fn main() {
   let data = Data::builder().with_field0(42).build();
+  assert_eq!(
+    data,
+    Data {
+      field0: 42,
+      field1: 0f32,
+      field2: Some(42),
+    }
+  );
   println!("{:?}", data);
 }
@@ -0,0 +1,22 @@
+use podstru_derive::{Ctor, Getters};
+use std::fmt::Debug;
+
+#[derive(Ctor, Getters, Debug, PartialEq)]
+#[ctor(const)]
+#[getters(const)]
+struct Data {
+  pub field0: usize,
+  pub field1: Option<f32>,
+  #[ctor(skip = "hello from 42")]
+  pub field2: &'static str,
+}
+
+static TABLE: [Data; 2] = [Data::new(0, None), Data::new(1, Some(1f32))];
+const FIELD0: &usize = TABLE[1].field0();
+
+fn main() {
+  assert_eq!(*FIELD0, 1);
+  assert_eq!(TABLE[0].field1(), None);
+  assert_eq!(TABLE[1].field2(), &"hello from 42");
+  println!("{:?}", TABLE);
+}
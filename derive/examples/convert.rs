@@ -0,0 +1,77 @@
+use podstru::Convert;
+
+#[derive(Debug, PartialEq)]
+pub struct UserId(pub u64);
+
+impl From<u64> for UserId {
+  fn from(id: u64) -> Self {
+    Self(id)
+  }
+}
+
+/// The domain type, which knows nothing about the transport layer.
+#[derive(Debug, PartialEq)]
+pub struct User {
+  pub id: UserId,
+  pub name: String,
+  pub email: String,
+  pub active: bool,
+}
+
+/// The wire format of the API.
+#[derive(Convert, Debug, Clone)]
+#[convert(into = "User")]
+pub struct UserDto {
+  pub id: u64,
+  #[convert(rename = "name")]
+  pub username: String,
+  #[convert(with = "normalize_email")]
+  pub email: String,
+  pub active: bool,
+}
+
+/// The row stored in the database, converted both ways.
+#[derive(Convert, Debug, PartialEq)]
+#[convert(from = "User", into = "User")]
+pub struct UserRow {
+  #[convert(from_with = "id_column")]
+  pub id: u64,
+  pub name: String,
+  pub email: String,
+  pub active: bool,
+  #[convert(skip, default = 1)]
+  pub version: u32,
+}
+
+fn normalize_email(email: String) -> String {
+  email.to_lowercase()
+}
+
+fn id_column(id: UserId) -> u64 {
+  id.0
+}
+
+fn main() {
+  let dto = UserDto {
+    id: 7,
+    username: "alice".into(),
+    email: "Alice@Example.com".into(),
+    active: true,
+  };
+  let user = User::from(dto.clone());
+  assert_eq!(
+    user,
+    User {
+      id: UserId(7),
+      name: "alice".into(),
+      email: "alice@example.com".into(),
+      active: true,
+    }
+  );
+
+  let row: UserRow = user.into();
+  assert_eq!(row.id, 7);
+  assert_eq!(row.version, 1);
+  assert_eq!(User::from(row).id, UserId(7));
+  println!("{:?}", dto);
+}
@@ -2,11 +2,23 @@ use podstru_derive::Ctor;
 use std::fmt::Debug;
 
 #[derive(Ctor, Debug, PartialEq)]
+#[ctor(name = "with_id", fields(id, field0))]
 struct Data {
   pub field0: usize,
   pub field1: f32,
   #[ctor(skip = "hello from 42")]
   pub field2: &'static str,
+  #[ctor(default)]
+  pub id: u32,
+  #[ctor(skip = field0 * 2)]
+  pub field4: usize,
+}
+
+#[derive(Ctor, Debug, PartialEq)]
+#[ctor(into)]
+struct User {
+  pub name: String,
+  pub email: String,
 }
 
 fn main() {
@@ -17,7 +29,23 @@ fn main() {
       field0: 42,
       field1: 1f32,
       field2: "hello from 42",
+      id: 0,
+      field4: 84,
+    }
+  );
+  let data = Data::with_id(7, 21);
+  assert_eq!(
+    data,
+    Data {
+      field0: 21,
+      field1: 0f32,
+      field2: "hello from 42",
+      id: 7,
+      field4: 42,
     }
   );
   println!("{:?}", data);
+  let user = User::new("alice", "alice@example.org");
+  assert_eq!(user.name, "alice");
+  println!("{:?}", user);
 }
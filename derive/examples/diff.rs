@@ -0,0 +1,80 @@
+use podstru::Diff;
+
+#[derive(Diff, Debug, Clone, PartialEq)]
+pub struct Address {
+  pub city: String,
+  pub zip: String,
+}
+
+#[derive(Diff, Debug, Clone, PartialEq)]
+pub struct Account {
+  pub name: String,
+  pub balance: i64,
+  #[diff(nested)]
+  pub address: Address,
+  #[pod(skip)]
+  pub last_seen: u64,
+  #[pod(secret)]
+  pub token: String,
+}
+
+/// Type parameters are compared and cloned like any other field.
+#[derive(Diff, Debug, Clone, PartialEq)]
+pub struct Tagged<T> {
+  pub tag: T,
+  pub count: u32,
+}
+
+fn main() {
+  let before = Account {
+    name: "alice".into(),
+    balance: 100,
+    address: Address {
+      city: "Paris".into(),
+      zip: "75001".into(),
+    },
+    last_seen: 1,
+    token: "hunter2".into(),
+  };
+  let mut after = before.clone();
+  after.balance = 80;
+  after.address.city = "Lyon".into();
+  after.last_seen = 2;
+  after.token = "s3cr3t".into();
+
+  let changes = before.diff(&after);
+  assert_eq!(
+    changes,
+    [
+      AccountChange::Balance { old: 100, new: 80 },
+      AccountChange::Address(vec![AddressChange::City {
+        old: "Paris".into(),
+        new: "Lyon".into(),
+      }]),
+      AccountChange::Token {
+        old: "hunter2".into(),
+        new: "s3cr3t".into(),
+      },
+    ]
+  );
+  // Secrets stay out of the audit log
+  assert_eq!(
+    format!("{:?}", changes[2]),
+    "Token { old: [REDACTED], new: [REDACTED] }"
+  );
+  for change in &changes {
+    println!("{:?}", change);
+  }
+
+  // Replay the audit log on a replica
+  let mut replica = before.clone();
+  replica.apply_changes(&changes);
+  assert_eq!(replica.balance, 80);
+  assert_eq!(replica.address, after.address);
+  assert_eq!(replica.last_seen, 1);
+  assert!(replica.diff(&replica).is_empty());
+
+  let tagged = Tagged { tag: 'a', count: 1 };
+  let changes = tagged.diff(&Tagged { tag: 'b', count: 1 });
+  assert_eq!(changes, [TaggedChange::Tag { old: 'a', new: 'b' }]);
+}
@@ -0,0 +1,88 @@
+use podstru::{Dynamic, FieldError, FieldNames};
+
+#[derive(Dynamic, FieldNames, Debug, PartialEq)]
+struct Config {
+  port: u16,
+  host: String,
+  timeout: Option<u32>,
+  #[dynamic(read_only)]
+  version: u32,
+  tags: Vec<String>,
+  #[pod(skip)]
+  secret: String,
+}
+
+/// Generic fields are read and written as `dyn Any` too, but are not parsed
+/// by `set_field_str`.
+#[derive(Dynamic)]
+struct Labeled<T> {
+  label: String,
+  value: T,
+}
+
+fn main() {
+  let mut config = Config {
+    port: 80,
+    host: "localhost".into(),
+    timeout: None,
+    version: 1,
+    tags: vec![],
+    secret: "hunter2".into(),
+  };
+  config.set_field_str("port", "8080").unwrap();
+  config.set_field_str("timeout", "30").unwrap();
+  assert_eq!(
+    config.get_field("port").unwrap().downcast_ref(),
+    Some(&8080u16)
+  );
+  assert_eq!(config.timeout, Some(30));
+  // The keys of `FieldNames` spare the typos
+  config.set_key_str(ConfigField::Port, "8081").unwrap();
+  assert_eq!(config.port, 8081);
+  assert_eq!(
+    config.get_key(ConfigField::Host).unwrap().downcast_ref(),
+    Some(&String::from("localhost"))
+  );
+  config.set_field_str("timeout", "").unwrap();
+  assert_eq!(config.timeout, None);
+
+  *config
+    .get_field_mut("host")
+    .unwrap()
+    .downcast_mut::<String>()
+    .unwrap() = "example.com".into();
+  assert_eq!(config.host, "example.com");
+
+  let error = config.set_field_str("secret", "x").unwrap_err();
+  assert!(matches!(error, FieldError::Unknown { .. }));
+  assert_eq!(error.to_string(), "unknown field `secret`");
+  assert_eq!(
+    config.set_field_str("version", "2"),
+    Err(FieldError::ReadOnly("version"))
+  );
+  assert!(config.get_field("version").is_some());
+  assert!(config.get_field_mut("version").is_none());
+  assert_eq!(
+    config.set_field_str("tags", "a,b"),
+    Err(FieldError::Unsupported("tags"))
+  );
+  let mut labeled = Labeled {
+    label: "ratio".into(),
+    value: 0.5f64,
+  };
+  *labeled
+    .get_field_mut("value")
+    .unwrap()
+    .downcast_mut::<f64>()
+    .unwrap() = 0.75;
+  labeled.set_field_str("label", "share").unwrap();
+  assert_eq!(
+    labeled.set_field_str("value", "0.25"),
+    Err(FieldError::Unsupported("value"))
+  );
+  assert_eq!((labeled.label.as_str(), labeled.value), ("share", 0.75));
+
+  let error = config.set_field_str("port", "http").unwrap_err();
+  println!("{}", error);
+  println!("{:?}", config);
+}
@@ -0,0 +1,42 @@
+use podstru::FieldNames;
+
+#[derive(FieldNames)]
+#[allow(dead_code)]
+pub struct Data {
+  id: u64,
+  display_name: String,
+  #[cfg(any())]
+  disabled: bool,
+  #[pod(skip)]
+  cache: Vec<u8>,
+  r#type: &'static str,
+}
+
+#[derive(FieldNames)]
+#[field_names(name = "PointKey")]
+#[allow(dead_code)]
+struct Point {
+  x: f32,
+  y: f32,
+}
+
+fn main() {
+  assert_eq!(Data::FIELD_NAMES, ["id", "display_name", "type"]);
+  assert_eq!(Data::FIELD_COUNT, 3);
+  assert_eq!(
+    DataField::ALL,
+    [DataField::Id, DataField::DisplayName, DataField::Type]
+  );
+  assert_eq!(DataField::DisplayName.as_str(), "display_name");
+  assert_eq!(DataField::Type.to_string(), "type");
+  assert_eq!("id".parse(), Ok(DataField::Id));
+  let error = "cache".parse::<DataField>().unwrap_err();
+  assert_eq!(error.to_string(), "unknown field `cache`");
+
+  assert_eq!(PointKey::ALL, [PointKey::X, PointKey::Y]);
+  assert_eq!(Point::FIELD_COUNT, 2);
+
+  for field in DataField::ALL {
+    println!("{:?} => {}", field, field);
+  }
+}
@@ -2,7 +2,9 @@ use podstru_derive::Fields;
 
 #[derive(Fields, Debug, PartialEq)]
 struct Data {
+  /// Copied onto `field0()`, `set_field0()`, ...
   pub field0: usize,
+  #[fields(attr(inline))]
   pub field1: f32,
   pub field2: Option<usize>,
   #[fields(skip)]
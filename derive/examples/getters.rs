@@ -3,9 +3,11 @@ use podstru_derive::Getters;
 #[derive(Getters, Debug)]
 struct Data {
   pub field0: usize,
+  #[getters(vis = "pub(crate)")]
   pub field1: f32,
   pub field2: Option<usize>,
   #[getters(skip)]
+  #[allow(dead_code)]
   pub field3: (),
 }
 
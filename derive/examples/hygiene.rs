@@ -0,0 +1,32 @@
+//! Generated code only refers to `::core` items, so neither shadowed prelude
+//! names nor `no_std` crates break it.
+#![allow(dead_code)]
+
+use podstru::{Builder, Ctor, Fields};
+
+struct Option;
+struct Some;
+struct Default;
+struct Result;
+trait Into {}
+
+#[derive(Builder, Ctor, Fields)]
+#[fields(track_changes)]
+struct Data {
+  #[builder(default = 42)]
+  #[pod(into)]
+  field0: u32,
+  field1: core::option::Option<u8>,
+  #[fields(dirty_flags)]
+  #[ctor(skip = 0)]
+  dirty: u8,
+}
+
+fn main() {
+  let mut data = Data::builder().with_field1(1).build();
+  data.set_field0(43u8);
+  assert_eq!(*data.field0(), 43);
+  assert!(data.is_field0_dirty());
+  assert_eq!(data.dirty_fields().count(), 1);
+  let _ = Data::new(1u8, core::option::Option::None);
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use podstru::Merge;
+
+#[derive(Merge, Debug, Default, PartialEq)]
+pub struct Server {
+  pub host: String,
+  pub port: Option<u16>,
+}
+
+#[derive(Merge, Debug, Default, PartialEq)]
+pub struct Config {
+  pub name: String,
+  #[merge(strategy = "keep")]
+  pub created_by: String,
+  #[merge(strategy = "append")]
+  pub plugins: Vec<String>,
+  #[merge(strategy = "append")]
+  pub env: BTreeMap<String, String>,
+  #[merge(strategy = "recurse")]
+  pub server: Server,
+  #[merge(strategy = "recurse")]
+  pub fallback: Option<Server>,
+  pub log_level: Option<String>,
+  #[cfg(not(target_os = "none"))]
+  pub retries: u8,
+}
+
+fn main() {
+  let mut config = Config {
+    name: "base".into(),
+    created_by: "ops".into(),
+    plugins: vec!["auth".into()],
+    env: BTreeMap::from([("MODE".into(), "prod".into())]),
+    server: Server {
+      host: "0.0.0.0".into(),
+      port: Some(80),
+    },
+    fallback: None,
+    log_level: Some("info".into()),
+    retries: 3,
+  };
+
+  // The local overlay only sets what it changes
+  config.merge(Config {
+    name: "local".into(),
+    created_by: "me".into(),
+    plugins: vec!["debug".into()],
+    env: BTreeMap::from([("MODE".into(), "dev".into())]),
+    server: Server {
+      host: "127.0.0.1".into(),
+      port: None,
+    },
+    fallback: Some(Server {
+      host: "backup".into(),
+      port: Some(8080),
+    }),
+    log_level: None,
+    retries: 5,
+  });
+
+  assert_eq!(config.name, "local");
+  assert_eq!(config.created_by, "ops");
+  assert_eq!(config.plugins, ["auth", "debug"]);
+  assert_eq!(config.env["MODE"], "dev");
+  assert_eq!(
+    config.server,
+    Server {
+      host: "127.0.0.1".into(),
+      port: Some(80),
+    }
+  );
+  assert_eq!(config.fallback.as_ref().unwrap().port, Some(8080));
+  assert_eq!(config.log_level.as_deref(), Some("info"));
+  assert_eq!(config.retries, 5);
+  println!("{:#?}", config);
+}
@@ -0,0 +1,73 @@
+use podstru::Partial;
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(derive(Debug, PartialEq))]
+pub struct Profile {
+  pub name: String,
+  pub bio: Option<String>,
+  pub age: u8,
+  #[pod(skip)]
+  pub revision: u32,
+}
+
+/// Only `diff_to_partial` requires the values to be comparable and cloneable.
+#[derive(Partial)]
+#[partial(derive(Debug, PartialEq))]
+pub struct Setting<T> {
+  pub key: String,
+  pub value: T,
+}
+
+fn main() {
+  let mut profile = Profile {
+    name: "alice".into(),
+    bio: Some("hello".into()),
+    age: 30,
+    revision: 1,
+  };
+
+  // PATCH { "age": 31, "bio": null }
+  profile.apply(PartialProfile {
+    age: Some(31),
+    bio: Some(None),
+    ..Default::default()
+  });
+  assert_eq!(profile.name, "alice");
+  assert_eq!(profile.bio, None);
+  assert_eq!(profile.age, 31);
+
+  let mut renamed = profile.clone();
+  renamed.name = "bob".into();
+  renamed.revision = 2;
+  let patch = profile.diff_to_partial(&renamed);
+  assert_eq!(
+    patch,
+    PartialProfile {
+      name: Some("bob".into()),
+      ..Default::default()
+    }
+  );
+  println!("{:?}", patch);
+
+  profile.apply(patch);
+  assert_eq!(profile.name, "bob");
+  assert_eq!(profile.revision, 1);
+
+  let mut setting = Setting {
+    key: "theme".into(),
+    value: 1u8,
+  };
+  let patch = setting.diff_to_partial(&Setting {
+    key: "theme".into(),
+    value: 2,
+  });
+  assert_eq!(
+    patch,
+    PartialSetting {
+      value: Some(2),
+      ..Default::default()
+    }
+  );
+  setting.apply(patch);
+  assert_eq!(setting.value, 2);
+}
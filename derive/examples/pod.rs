@@ -0,0 +1,29 @@
+use podstru_derive::{Builder, Ctor, Fields};
+use podstru_internal::Builder;
+
+#[derive(Builder, Ctor, Fields, Debug, PartialEq)]
+#[pod(into)]
+struct Account {
+  #[pod(rename = "login")]
+  user_name: String,
+  #[fields(vis = "pub(crate)")]
+  email: String,
+  #[pod(skip)]
+  password_hash: String,
+}
+
+fn main() {
+  // Skipped fields are left out of the constructor parameters too
+  let mut account = Account::new("jdoe", "jdoe@example.com");
+  assert_eq!(account.password_hash, "");
+  account.set_login("john");
+  assert_eq!(account.login(), "john");
+  assert_eq!(account.email(), "jdoe@example.com");
+
+  let built = Account::builder()
+    .with_login("john")
+    .with_email("jdoe@example.com")
+    .build();
+  assert_eq!(built.password_hash, "");
+  println!("{:?}", account);
+}
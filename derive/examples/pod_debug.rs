@@ -0,0 +1,55 @@
+use std::fmt;
+
+use podstru::{Getters, PodDebug, Secret};
+
+#[derive(PodDebug, Getters)]
+pub struct Credentials {
+  pub user: String,
+  #[pod(secret)]
+  pub password: String,
+  #[debug(with = "last_digits")]
+  pub card: String,
+  #[debug(redact)]
+  pub session: Option<u64>,
+  #[debug(skip)]
+  pub cache: Vec<u8>,
+}
+
+fn last_digits(card: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  write!(f, "\"****{}\"", &card[card.len() - 4..])
+}
+
+/// `pod` derives `PodDebug` in place of `Debug` for structs holding secrets.
+#[podstru::pod(builder)]
+pub struct Database {
+  pub url: String,
+  #[pod(secret)]
+  pub token: String,
+}
+
+fn main() {
+  let credentials = Credentials {
+    user: "alice".into(),
+    password: "hunter2".into(),
+    card: "4242424242424242".into(),
+    session: Some(42),
+    cache: vec![1, 2, 3],
+  };
+  let debug = format!("{:?}", credentials);
+  assert_eq!(
+    debug,
+    r#"Credentials { user: "alice", password: [REDACTED], card: "****4242", session: [REDACTED], .. }"#
+  );
+
+  // The getter of a secret is redacted as well, until exposed
+  let password: Secret<&String> = credentials.password();
+  assert_eq!(format!("{:?}", password), "[REDACTED]");
+  assert_eq!(password.expose(), "hunter2");
+
+  let database = Database {
+    url: "postgres://localhost".into(),
+    token: "s3cr3t".into(),
+  };
+  assert!(!format!("{:?}", database).contains("s3cr3t"));
+  println!("{}\n{:#?}", debug, database);
+}
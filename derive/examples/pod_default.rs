@@ -0,0 +1,51 @@
+use podstru::{Builder, PodDefault};
+
+#[derive(PodDefault, Builder, Debug, PartialEq)]
+pub struct Settings {
+  #[default("localhost".into())]
+  pub host: String,
+  #[default(8080)]
+  pub port: u16,
+  #[default(30)]
+  pub timeout: Option<u64>,
+  pub verbose: bool,
+  #[default([1, 2, 4, 8].iter().map(|retry| retry * 100).collect())]
+  pub backoff_ms: Vec<u64>,
+}
+
+#[podstru::pod(ctor)]
+pub struct Retry {
+  #[default(3)]
+  pub attempts: u32,
+  pub backoff_ms: u64,
+}
+
+/// Type parameters must implement `Default` too, as with the standard derive.
+#[derive(PodDefault, Debug)]
+pub struct Range<T> {
+  pub start: T,
+  #[default(10)]
+  pub len: usize,
+}
+
+fn main() {
+  let settings = Settings::default();
+  assert_eq!(settings.host, "localhost");
+  assert_eq!(settings.port, 8080);
+  assert_eq!(settings.timeout, Some(30));
+  assert!(!settings.verbose);
+  assert_eq!(settings.backoff_ms, [100, 200, 400, 800]);
+  let range = Range::<u8>::default();
+  assert_eq!((range.start, range.len), (0, 10));
+
+  // The builder falls back to the very same values
+  assert_eq!(Settings::builder().build(), settings);
+  let custom = Settings::builder().with_port(9000).build();
+  assert_eq!(custom.host, settings.host);
+  assert_eq!(custom.port, 9000);
+
+  // `pod` picks `PodDefault` over the standard `Default` as soon as a field
+  // has a default value
+  assert_eq!(Retry::default(), Retry::new(3, 0));
+  println!("{:?}\n{:?}", settings, Retry::default());
+}
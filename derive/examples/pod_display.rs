@@ -0,0 +1,73 @@
+use podstru::PodDisplay;
+
+#[derive(PodDisplay)]
+#[display("{name} (#{id:04}) owes {balance}")]
+pub struct Customer {
+  pub id: u32,
+  pub name: String,
+  #[display(fmt = "{:.2} EUR")]
+  pub balance: f64,
+}
+
+/// Without a template, fields are written as `key=value` pairs.
+#[derive(PodDisplay)]
+pub struct Request {
+  pub method: &'static str,
+  pub path: String,
+  #[display(fmt = "{}ms")]
+  pub elapsed: u64,
+  pub user: Option<String>,
+  #[pod(secret)]
+  pub token: String,
+  #[display(skip)]
+  pub body: Vec<u8>,
+}
+
+#[derive(PodDisplay)]
+#[display(separator = ", ")]
+pub struct Point {
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(PodDisplay)]
+#[display("{value} {unit}")]
+pub struct Measure<T> {
+  pub value: T,
+  pub unit: &'static str,
+}
+
+fn main() {
+  let customer = Customer {
+    id: 42,
+    name: "Alice".into(),
+    balance: 12.5,
+  };
+  assert_eq!(customer.to_string(), "Alice (#0042) owes 12.50 EUR");
+
+  let mut request = Request {
+    method: "GET",
+    path: "/users".into(),
+    elapsed: 12,
+    user: None,
+    token: "hunter2".into(),
+    body: vec![],
+  };
+  assert_eq!(
+    request.to_string(),
+    "method=GET path=/users elapsed=12ms token=[REDACTED]"
+  );
+  request.user = Some("alice".into());
+  assert_eq!(
+    request.to_string(),
+    "method=GET path=/users elapsed=12ms user=alice token=[REDACTED]"
+  );
+
+  assert_eq!(Point { x: 1, y: -2 }.to_string(), "x=1, y=-2");
+  let measure = Measure {
+    value: 2.5,
+    unit: "kg",
+  };
+  assert_eq!(measure.to_string(), "2.5 kg");
+  println!("{}\n{}", customer, request);
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use podstru::{PodEq, PodHash, PodOrd};
+
+/// Entities are the same as long as their id is.
+#[derive(PodEq, PodHash, Debug)]
+pub struct User {
+  pub id: u64,
+  #[eq(skip)]
+  pub name: String,
+}
+
+/// Cached entries compare by content, whenever they were fetched.
+#[derive(PodEq, PodHash, Debug)]
+pub struct Entry {
+  pub key: String,
+  pub value: Vec<u8>,
+  #[eq(skip)]
+  pub fetched_at: u64,
+}
+
+/// Tasks sort by priority, highest first, then by deadline.
+#[derive(PodEq, PodOrd, Debug)]
+pub struct Task {
+  pub title: &'static str,
+  #[ord(priority = 2)]
+  pub deadline: u32,
+  #[ord(priority = 1, reverse)]
+  pub urgency: u8,
+}
+
+#[derive(PodEq, PodOrd, Debug)]
+#[eq(partial)]
+#[ord(partial)]
+pub struct Reading {
+  pub value: f64,
+  #[eq(skip)]
+  pub sensor: &'static str,
+}
+
+fn main() {
+  let before = User {
+    id: 1,
+    name: "alice".into(),
+  };
+  let after = User {
+    id: 1,
+    name: "Alice".into(),
+  };
+  assert_eq!(before, after);
+  assert_eq!(HashSet::from([before, after]).len(), 1);
+
+  let entry = |fetched_at| Entry {
+    key: "k".into(),
+    value: vec![1],
+    fetched_at,
+  };
+  assert_eq!(entry(1), entry(2));
+
+  let mut tasks = vec![
+    Task {
+      title: "docs",
+      deadline: 3,
+      urgency: 1,
+    },
+    Task {
+      title: "bug",
+      deadline: 5,
+      urgency: 9,
+    },
+    Task {
+      title: "release",
+      deadline: 2,
+      urgency: 9,
+    },
+  ];
+  tasks.sort();
+  let titles: Vec<_> = tasks.iter().map(|task| task.title).collect();
+  assert_eq!(titles, ["release", "bug", "docs"]);
+
+  let low = Reading {
+    value: 1.5,
+    sensor: "a",
+  };
+  let high = Reading {
+    value: 2.5,
+    sensor: "b",
+  };
+  assert!(low < high);
+  println!("{:?}", tasks);
+}
@@ -0,0 +1,40 @@
+use podstru::Builder;
+
+#[podstru::pod(builder, ctor, fields, into)]
+struct User {
+  #[pod(rename = "login")]
+  name: String,
+  email: String,
+  #[pod(skip)]
+  #[ctor(default)]
+  visits: u32,
+}
+
+/// `PodOrd` brings `PodEq` along, for the `Eq` it requires.
+#[podstru::pod(pod_ord)]
+struct Version {
+  major: u8,
+  minor: u8,
+}
+
+fn main() {
+  let mut user = User::new("jdoe", "jdoe@example.com");
+  user.set_login("john");
+  assert_eq!(user.login(), "john");
+
+  let built = User::builder()
+    .with_login("john")
+    .with_email("jdoe@example.com")
+    .build();
+  assert_eq!(built, user.clone());
+  assert_eq!(User::default().visits, 0);
+  println!("{:?}", user);
+
+  let version = Version { major: 1, minor: 2 };
+  let next = Version {
+    major: 1,
+    minor: 10,
+  };
+  assert!(version < next);
+  assert_eq!(version, version.clone());
+}
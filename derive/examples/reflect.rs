@@ -0,0 +1,41 @@
+use podstru::{FieldInfo, Reflect};
+
+#[derive(Reflect)]
+#[allow(dead_code)]
+pub struct User {
+  /// Login of the user.
+  #[reflect(meta(label = "Name", widget = "text"))]
+  pub name: String,
+  pub(crate) email: Option<String>,
+  #[cfg(any())]
+  disabled: bool,
+  #[pod(skip)]
+  password_hash: String,
+  visits: Vec<(u32, &'static str)>,
+}
+
+/// Print the columns of any reflected struct.
+fn print_columns<T: Reflect>() {
+  println!("{}", T::NAME);
+  for FieldInfo {
+    name,
+    type_name,
+    vis,
+    ..
+  } in T::FIELDS
+  {
+    println!("  {:<10} {:<30} {}", name, type_name, vis);
+  }
+}
+
+fn main() {
+  let names: Vec<_> = User::FIELDS.iter().map(|field| field.name).collect();
+  assert_eq!(names, ["name", "email", "visits"]);
+  let name = User::field_info("name").unwrap();
+  assert_eq!(name.docs, "Login of the user.");
+  assert_eq!(name.meta("label"), Some("Name"));
+  assert_eq!(User::FIELDS[1].type_name, "Option<String>");
+  assert_eq!(User::FIELDS[1].vis, "pub(crate)");
+  assert_eq!(User::FIELDS[2].type_name, "Vec<(u32, &'static str)>");
+  print_columns::<User>();
+}
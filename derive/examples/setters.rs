@@ -28,5 +28,10 @@ fn main() {
       field3: ()
     }
   );
+  data.update_field0(|field0| *field0 *= 2);
+  assert_eq!(data.replace_field1(2f32), -1f32);
+  assert_eq!(data.take_field2(), Some(12));
+  *data.get_or_insert_field2_with(|| 7) += 1;
+  assert_eq!((data.field0, data.field1, data.field2), (66, 2f32, Some(8)));
   println!("{:?}", data);
 }
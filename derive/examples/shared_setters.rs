@@ -0,0 +1,66 @@
+use std::{rc::Rc, sync::Arc};
+
+use podstru::{FieldNames, Fields, Setters, TrackChanges};
+
+/// Kept behind references, `with_*` copies it instead of consuming it.
+#[derive(Setters, FieldNames, Debug, Clone, PartialEq)]
+#[setters(cloning, track_changes)]
+struct Theme {
+  pub name: String,
+  pub accent: u32,
+  pub font: Option<String>,
+  #[setters(dirty_flags)]
+  dirty: u8,
+}
+
+/// Shared between threads, `with_*` only clones it while other handles exist.
+#[derive(Fields, Debug, Clone, PartialEq)]
+#[fields(shared = "Arc", into)]
+struct Config {
+  pub host: String,
+  pub port: u16,
+}
+
+#[derive(Setters, Debug, Clone, PartialEq)]
+#[setters(shared = "Rc")]
+struct Layout {
+  pub columns: u8,
+}
+
+fn main() {
+  let base = Theme {
+    name: "light".into(),
+    accent: 0x3366ff,
+    font: None,
+    dirty: 0,
+  };
+  let dark = base.with_name("dark".into()).with_accent(0x222222);
+  assert_eq!(base.name, "light");
+  assert_eq!(base.dirty_fields().count(), 0);
+  assert_eq!(dark.name, "dark");
+  assert_eq!(dark.font, base.font);
+  assert_eq!(dark.dirty_fields().collect::<Vec<_>>(), ["name", "accent"]);
+  // Keyed by the `FieldNames` enum rather than by name
+  assert_eq!(
+    dark.dirty_keys().collect::<Vec<_>>(),
+    [ThemeField::Name, ThemeField::Accent]
+  );
+
+  let config = Arc::new(Config {
+    host: "localhost".into(),
+    port: 8080,
+  });
+  let handle = Arc::clone(&config);
+  let updated = config.with_port(9090u16);
+  assert_eq!(handle.port(), &8080);
+  assert_eq!(updated.port(), &9090);
+  // Without other handles, the update happens in place
+  let ptr = Arc::as_ptr(&updated);
+  let updated = updated.with_host("example.org");
+  assert_eq!(Arc::as_ptr(&updated), ptr);
+  assert_eq!(updated.host(), "example.org");
+
+  let layout = Rc::new(Layout { columns: 2 }).with_columns(3);
+  assert_eq!(layout.columns, 3);
+  println!("{:?} {:?} {:?}", dark, updated, layout);
+}
@@ -0,0 +1,42 @@
+use podstru_derive::Setters;
+
+#[derive(Setters, Debug, PartialEq)]
+#[setters(track_changes)]
+struct Data {
+  pub field0: usize,
+  pub field1: f32,
+  pub field2: Option<usize>,
+  #[setters(dirty_flags)]
+  dirty: u8,
+}
+
+fn main() {
+  let mut data = Data {
+    field0: 42,
+    field1: 0f32,
+    field2: Some(84),
+    dirty: 0,
+  };
+  assert_eq!(data.dirty_fields().count(), 0);
+  data.set_field0(33);
+  *data.field2_mut() = None;
+  assert!(data.is_field0_dirty());
+  assert!(!data.is_field1_dirty());
+  assert!(data.is_field2_dirty());
+  assert_eq!(
+    data.dirty_fields().collect::<Vec<_>>(),
+    ["field0", "field2"]
+  );
+  data.clear_dirty();
+  assert_eq!(data.dirty_fields().count(), 0);
+  let mut data = data.with_field1(-1f32);
+  assert_eq!(data.dirty_fields().collect::<Vec<_>>(), ["field1"]);
+  data.clear_dirty();
+  data.update_field0(|field0| *field0 += 1);
+  data.take_field2();
+  assert_eq!(
+    data.dirty_fields().collect::<Vec<_>>(),
+    ["field0", "field2"]
+  );
+  println!("{:?}", data);
+}
@@ -0,0 +1,46 @@
+use podstru_derive::Ctor;
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq)]
+enum RangeError {
+  Empty,
+  Reversed,
+}
+
+fn not_empty(name: &&'static str) -> Result<(), RangeError> {
+  match name.is_empty() {
+    true => Err(RangeError::Empty),
+    false => Ok(()),
+  }
+}
+
+fn ordered(range: &Range) -> Result<(), RangeError> {
+  match range.min <= range.max {
+    true => Ok(()),
+    false => Err(RangeError::Reversed),
+  }
+}
+
+#[derive(Ctor, Debug, PartialEq)]
+#[ctor(validate = "ordered", error = "RangeError")]
+struct Range {
+  #[ctor(check = "not_empty")]
+  pub name: &'static str,
+  pub min: i32,
+  pub max: i32,
+}
+
+fn main() {
+  let range = Range::try_new("percent", 0, 100);
+  assert_eq!(
+    range,
+    Ok(Range {
+      name: "percent",
+      min: 0,
+      max: 100,
+    })
+  );
+  assert_eq!(Range::try_new("", 0, 100), Err(RangeError::Empty));
+  assert_eq!(Range::try_new("percent", 100, 0), Err(RangeError::Reversed));
+  println!("{:?}", range);
+}
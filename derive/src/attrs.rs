@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use syn::{
+  ext::IdentExt, parse::Parse, punctuated::Punctuated, spanned::Spanned, token, Attribute,
+  DeriveInput, Expr, ExprLit, Field, FieldsNamed, Ident, Lit, LitStr, MacroDelimiter, Meta,
+  MetaList, MetaNameValue, Path, Visibility,
+};
+
+/// Accumulates diagnostics so that a single compile pass reports every
+/// problem instead of the first one.
+#[derive(Default)]
+pub(crate) struct Errors(Option<syn::Error>);
+
+impl Errors {
+  pub(crate) fn push(&mut self, error: syn::Error) {
+    match &mut self.0 {
+      Some(errors) => errors.combine(error),
+      None => self.0 = Some(error),
+    }
+  }
+
+  /// Record the error of `result`, if any, and return its value otherwise.
+  pub(crate) fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+    match result {
+      Ok(value) => Some(value),
+      Err(error) => {
+        self.push(error);
+        None
+      }
+    }
+  }
+
+  pub(crate) fn finish(self) -> syn::Result<()> {
+    match self.0 {
+      Some(errors) => Err(errors),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Shape of the value expected by a helper attribute key.
+pub(crate) enum Kind {
+  /// A bare key: `skip`.
+  Flag,
+  /// Any expression: `default = 42`.
+  Expr,
+  /// A string literal: `vis = "pub(crate)"`.
+  Str,
+  /// A function path, bare or quoted: `check = "path::to::fn"`.
+  Path,
+  /// A parenthesized list: `attr(inline)`.
+  List,
+}
+
+/// A key accepted by a helper attribute.
+pub(crate) struct Key {
+  pub(crate) name: &'static str,
+  pub(crate) kind: Kind,
+  /// Appended to the error reported when the key has the wrong shape.
+  pub(crate) hint: &'static str,
+}
+
+impl Key {
+  const fn new(name: &'static str, kind: Kind, hint: &'static str) -> Self {
+    Self { name, kind, hint }
+  }
+}
+
+/// The helper attribute of a derive, along with the keys it accepts on the
+/// struct and on its fields.
+pub(crate) struct Namespace {
+  pub(crate) attr: &'static str,
+  /// What the attribute configures, for diagnostics.
+  pub(crate) owner: &'static str,
+  pub(crate) item: &'static [Key],
+  pub(crate) field: &'static [Key],
+}
+
+const SKIP: Key = Key::new("skip", Kind::Flag, "");
+const VIS: Key = Key::new("vis", Kind::Str, ", e.g. `vis = \"pub(crate)\"`");
+const ATTR: Key = Key::new("attr", Kind::List, ", e.g. `attr(inline, must_use)`");
+const CONST: Key = Key::new("const", Kind::Flag, "");
+const TRACK_CHANGES: Key = Key::new("track_changes", Kind::Flag, "");
+const DIRTY_FLAGS: Key = Key::new("dirty_flags", Kind::Flag, "");
+const CLONING: Key = Key::new("cloning", Kind::Flag, "");
+const SHARED: Key = Key::new("shared", Kind::Str, ", e.g. `shared = \"Arc\"`");
+const RENAME: Key = Key::new("rename", Kind::Str, ", e.g. `rename = \"id\"`");
+const INTO: Key = Key::new("into", Kind::Flag, "");
+/// Only implement the `Partial*` trait, for fields such as floats.
+const PARTIAL_ONLY: Key = Key::new("partial", Kind::Flag, "");
+const CRATE: Key = Key::new("crate", Kind::Str, ", e.g. `crate = \"my_podstru\"`");
+
+/// Arguments of the `#[podstru::pod(...)]` attribute macro: the derives to
+/// apply, and the struct-wide options forwarded to them.
+pub(crate) const POD_MACRO: Namespace = Namespace {
+  attr: "pod",
+  owner: "the `pod` attribute macro",
+  item: &[
+    Key::new("builder", Kind::Flag, ""),
+    Key::new("convert", Kind::Flag, ""),
+    Key::new("ctor", Kind::Flag, ""),
+    Key::new("diff", Kind::Flag, ""),
+    Key::new("dynamic", Kind::Flag, ""),
+    Key::new("field_names", Kind::Flag, ""),
+    Key::new("fields", Kind::Flag, ""),
+    Key::new("getters", Kind::Flag, ""),
+    Key::new("merge", Kind::Flag, ""),
+    Key::new("partial", Kind::Flag, ""),
+    Key::new("pod_debug", Kind::Flag, ""),
+    Key::new("pod_default", Kind::Flag, ""),
+    Key::new("pod_display", Kind::Flag, ""),
+    Key::new("pod_eq", Kind::Flag, ""),
+    Key::new("pod_hash", Kind::Flag, ""),
+    Key::new("pod_ord", Kind::Flag, ""),
+    Key::new("reflect", Kind::Flag, ""),
+    Key::new("setters", Kind::Flag, ""),
+    VIS,
+    INTO,
+    CRATE,
+  ],
+  field: &[],
+};
+
+/// Options shared by every derive, which their own attribute overrides.
+pub(crate) const POD: Namespace = Namespace {
+  attr: "pod",
+  owner: "podstru derive macros",
+  item: &[VIS, INTO, CRATE],
+  field: &[SKIP, RENAME, VIS, INTO, Key::new("secret", Kind::Flag, "")],
+};
+
+pub(crate) const BUILDER: Namespace = Namespace {
+  attr: "builder",
+  owner: "`Builder` derive macro",
+  item: &[VIS, ATTR, INTO, CRATE],
+  field: &[
+    Key::new("default", Kind::Expr, ": the default value of the field"),
+    SKIP,
+    RENAME,
+    VIS,
+    ATTR,
+    INTO,
+  ],
+};
+
+/// Fields left out of `PodEq`, and therefore of `PodHash` and `PodOrd` too,
+/// which must agree with it.
+pub(crate) const EQ: Namespace = Namespace {
+  attr: "eq",
+  owner: "`PodEq` derive macro",
+  item: &[PARTIAL_ONLY],
+  field: &[SKIP],
+};
+
+pub(crate) const FIELD_NAMES: Namespace = Namespace {
+  attr: "field_names",
+  owner: "`FieldNames` derive macro",
+  item: &[
+    Key::new("name", Kind::Str, ", e.g. `name = \"DataKey\"`"),
+    CRATE,
+  ],
+  field: &[SKIP],
+};
+
+pub(crate) const GETTERS: Namespace = Namespace {
+  attr: "getters",
+  owner: "`Getters` derive macro",
+  item: &[VIS, ATTR, CONST, CRATE],
+  field: &[SKIP, RENAME, VIS, ATTR, CONST],
+};
+
+pub(crate) const SETTERS: Namespace = Namespace {
+  attr: "setters",
+  owner: "`Setters` derive macro",
+  item: &[VIS, ATTR, INTO, TRACK_CHANGES, CLONING, SHARED, CRATE],
+  field: &[SKIP, RENAME, VIS, ATTR, INTO, DIRTY_FLAGS],
+};
+
+pub(crate) const FIELDS: Namespace = Namespace {
+  attr: "fields",
+  owner: "`Fields` derive macro",
+  item: &[
+    VIS,
+    ATTR,
+    CONST,
+    INTO,
+    TRACK_CHANGES,
+    CLONING,
+    SHARED,
+    CRATE,
+  ],
+  field: &[SKIP, RENAME, VIS, ATTR, CONST, INTO, DIRTY_FLAGS],
+};
+
+pub(crate) const CONVERT: Namespace = Namespace {
+  attr: "convert",
+  owner: "`Convert` derive macro",
+  item: &[
+    Key::new("into", Kind::Str, ", e.g. `into = \"DomainUser\"`"),
+    Key::new("from", Kind::Str, ", e.g. `from = \"UserDto\"`"),
+  ],
+  field: &[
+    SKIP,
+    Key::new("default", Kind::Expr, ": the value of the skipped field"),
+    RENAME,
+    Key::new("with", Kind::Path, ", e.g. `with = \"path::to::fn\"`"),
+    Key::new(
+      "into_with",
+      Kind::Path,
+      ", e.g. `into_with = \"path::to::fn\"`",
+    ),
+    Key::new(
+      "from_with",
+      Kind::Path,
+      ", e.g. `from_with = \"path::to::fn\"`",
+    ),
+  ],
+};
+
+pub(crate) const DIFF: Namespace = Namespace {
+  attr: "diff",
+  owner: "`Diff` derive macro",
+  item: &[
+    Key::new("name", Kind::Str, ", e.g. `name = \"DataEvent\"`"),
+    Key::new("derive", Kind::List, ", e.g. `derive(Eq, Hash)`"),
+    CRATE,
+  ],
+  field: &[SKIP, Key::new("nested", Kind::Flag, "")],
+};
+
+pub(crate) const DEBUG: Namespace = Namespace {
+  attr: "debug",
+  owner: "`PodDebug` derive macro",
+  item: &[CRATE],
+  field: &[
+    SKIP,
+    Key::new("redact", Kind::Flag, ""),
+    Key::new("with", Kind::Path, ", e.g. `with = \"path::to::fn\"`"),
+  ],
+};
+
+/// Field options of `PodDisplay`, whose template is given by a bare
+/// `#[display("...")]` on the struct.
+pub(crate) const DISPLAY: Namespace = Namespace {
+  attr: "display",
+  owner: "`PodDisplay` derive macro",
+  item: &[Key::new(
+    "separator",
+    Kind::Str,
+    ", e.g. `separator = \", \"`",
+  )],
+  field: &[SKIP, Key::new("fmt", Kind::Str, ", e.g. `fmt = \"{:.2}\"`")],
+};
+
+pub(crate) const DYNAMIC: Namespace = Namespace {
+  attr: "dynamic",
+  owner: "`Dynamic` derive macro",
+  item: &[CRATE],
+  field: &[SKIP, Key::new("read_only", Kind::Flag, "")],
+};
+
+pub(crate) const HASH: Namespace = Namespace {
+  attr: "hash",
+  owner: "`PodHash` derive macro",
+  item: &[],
+  field: &[SKIP],
+};
+
+pub(crate) const MERGE: Namespace = Namespace {
+  attr: "merge",
+  owner: "`Merge` derive macro",
+  item: &[CRATE],
+  field: &[
+    SKIP,
+    Key::new("strategy", Kind::Str, ", e.g. `strategy = \"append\"`"),
+  ],
+};
+
+pub(crate) const ORD: Namespace = Namespace {
+  attr: "ord",
+  owner: "`PodOrd` derive macro",
+  item: &[PARTIAL_ONLY],
+  field: &[
+    SKIP,
+    Key::new(
+      "priority",
+      Kind::Expr,
+      ": fields with a lower one are compared first, e.g. `priority = 1`",
+    ),
+    Key::new("reverse", Kind::Flag, ""),
+  ],
+};
+
+pub(crate) const PARTIAL: Namespace = Namespace {
+  attr: "partial",
+  owner: "`Partial` derive macro",
+  item: &[
+    Key::new("name", Kind::Str, ", e.g. `name = \"DataPatch\"`"),
+    Key::new("derive", Kind::List, ", e.g. `derive(Debug, Clone)`"),
+    VIS,
+  ],
+  field: &[SKIP],
+};
+
+pub(crate) const REFLECT: Namespace = Namespace {
+  attr: "reflect",
+  owner: "`Reflect` derive macro",
+  item: &[CRATE],
+  field: &[
+    SKIP,
+    Key::new("meta", Kind::List, ", e.g. `meta(label = \"Name\")`"),
+  ],
+};
+
+pub(crate) const CTOR: Namespace = Namespace {
+  attr: "ctor",
+  owner: "`Ctor` derive macro",
+  item: &[
+    VIS,
+    CONST,
+    Key::new("name", Kind::Str, ", e.g. `name = \"with_id\"`"),
+    Key::new("fields", Kind::List, ", e.g. `fields(id, name)`"),
+    Key::new(
+      "validate",
+      Kind::Path,
+      ", e.g. `validate = \"path::to::fn\"`",
+    ),
+    Key::new("error", Kind::Str, ", e.g. `error = \"MyError\"`"),
+    INTO,
+  ],
+  field: &[
+    Key::new(
+      "skip",
+      Kind::Expr,
+      ": the default value of the skipped field, or use `#[ctor(default)]`",
+    ),
+    Key::new("default", Kind::Flag, ""),
+    Key::new("check", Kind::Path, ", e.g. `check = \"path::to::fn\"`"),
+    INTO,
+  ],
+};
+
+/// The validated arguments of every `#[<attr>(...)]` attribute found on the
+/// struct or on one of its fields, grouped by attribute.
+#[derive(Clone, Default)]
+pub(crate) struct Args {
+  groups: Vec<Vec<Meta>>,
+}
+
+impl Args {
+  fn parse(
+    attrs: &[Attribute],
+    ns: &Namespace,
+    keys: &[Key],
+    other_keys: (&[Key], &str),
+    errors: &mut Errors,
+  ) -> Self {
+    let mut groups = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(ns.attr)) {
+      let Some(metas) = errors.check(parse_metas(attr)) else {
+        continue;
+      };
+      let mut group: Vec<Meta> = vec![];
+      for meta in metas {
+        let name = meta
+          .path()
+          .get_ident()
+          .map(|ident| ident.unraw().to_string());
+        let Some(key) = keys.iter().find(|key| name.as_deref() == Some(key.name)) else {
+          errors.push(unknown_key(&meta, name, ns, keys, other_keys));
+          continue;
+        };
+        if group.iter().any(|other| other.path().is_ident(key.name)) {
+          errors.push(syn::Error::new(
+            meta.path().span(),
+            format!("duplicate `{}` attribute", key.name),
+          ));
+          continue;
+        }
+        match check_kind(&meta, key, ns) {
+          Ok(()) => group.push(meta),
+          Err(error) => errors.push(error),
+        }
+      }
+      groups.push(group);
+    }
+    Self { groups }
+  }
+
+  /// Parse the arguments of an attribute macro named after `ns`.
+  pub(crate) fn parse_macro(args: TokenStream, ns: &Namespace) -> syn::Result<Self> {
+    let attr_name = Ident::new(ns.attr, Span::call_site());
+    let attr: Attribute = parse_quote!(#[#attr_name(#args)]);
+    let mut errors = Errors::default();
+    let args = Self::parse(&[attr], ns, ns.item, (ns.field, "fields"), &mut errors);
+    errors.finish()?;
+    Ok(args)
+  }
+
+  fn find(&self, key: &str) -> Option<&Meta> {
+    self.metas().find(|meta| meta.path().is_ident(key))
+  }
+
+  fn metas(&self) -> impl Iterator<Item = &Meta> {
+    self.groups.iter().flatten()
+  }
+
+  /// One `Args` per attribute, for keys which only make sense together.
+  pub(crate) fn split(&self) -> impl Iterator<Item = Args> + '_ {
+    self.groups.iter().map(|group| Args {
+      groups: vec![group.clone()],
+    })
+  }
+
+  pub(crate) fn flag(&self, key: &str) -> Option<&Path> {
+    match self.find(key)? {
+      Meta::Path(path) => Some(path),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn expr(&self, key: &str) -> Option<&Expr> {
+    match self.find(key)? {
+      Meta::NameValue(meta_name_value) => Some(&meta_name_value.value),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn lit_str(&self, key: &str) -> Option<&LitStr> {
+    match self.expr(key)? {
+      Expr::Lit(ExprLit {
+        lit: Lit::Str(lit), ..
+      }) => Some(lit),
+      _ => None,
+    }
+  }
+
+  /// Parse the string literal given to `key`, e.g. `vis = "pub(crate)"`.
+  pub(crate) fn parse_str<T: Parse>(&self, key: &str) -> syn::Result<Option<T>> {
+    self.lit_str(key).map(LitStr::parse).transpose()
+  }
+
+  /// The function path given to `key`, bare or quoted.
+  pub(crate) fn path(&self, key: &str) -> syn::Result<Option<Path>> {
+    match self.expr(key) {
+      Some(Expr::Path(path)) => Ok(Some(path.path.clone())),
+      Some(_) => self.parse_str(key),
+      None => Ok(None),
+    }
+  }
+
+  pub(crate) fn lists<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a MetaList> + 'a {
+    self.metas().filter_map(move |meta| match meta {
+      Meta::List(list) if list.path.is_ident(key) => Some(list),
+      _ => None,
+    })
+  }
+
+  pub(crate) fn list<'a>(&'a self, key: &'a str) -> Option<&'a MetaList> {
+    self.lists(key).next()
+  }
+
+  /// Span of `key`, to point diagnostics at.
+  pub(crate) fn span(&self, key: &str) -> Option<Span> {
+    self.find(key).map(|meta| meta.path().span())
+  }
+}
+
+/// The helper attribute arguments of a derive, for the struct and each field,
+/// along with the shared `#[pod(...)]` ones.
+pub(crate) struct DeriveAttrs {
+  pub(crate) item: Args,
+  fields: HashMap<Ident, Args>,
+  pod_item: Args,
+  pod_fields: HashMap<Ident, Args>,
+  /// Base name of the methods generated for each field, from `rename`.
+  names: HashMap<Ident, Ident>,
+  /// Path of the `podstru` crate, from `crate`.
+  krate: Path,
+}
+
+impl DeriveAttrs {
+  pub(crate) fn parse(
+    input: &DeriveInput,
+    fields: &FieldsNamed,
+    ns: &Namespace,
+  ) -> syn::Result<Self> {
+    let mut errors = Errors::default();
+    let parse_item = |ns: &Namespace, errors: &mut Errors| {
+      Args::parse(&input.attrs, ns, ns.item, (ns.field, "fields"), errors)
+    };
+    let parse_fields = |ns: &Namespace, errors: &mut Errors| -> HashMap<Ident, Args> {
+      fields
+        .named
+        .iter()
+        .map(|f| {
+          let args = Args::parse(&f.attrs, ns, ns.field, (ns.item, "the struct"), errors);
+          (f.ident.clone().unwrap(), args)
+        })
+        .collect()
+    };
+    let item = parse_item(ns, &mut errors);
+    let fields = parse_fields(ns, &mut errors);
+    let pod_item = parse_item(&POD, &mut errors);
+    let pod_fields = parse_fields(&POD, &mut errors);
+    let names = fields
+      .iter()
+      .map(|(field_name, args)| {
+        let rename = match args.lit_str("rename") {
+          Some(_) => args.parse_str::<Ident>("rename"),
+          None => pod_fields[field_name].parse_str::<Ident>("rename"),
+        };
+        let name = errors.check(rename).flatten();
+        (
+          field_name.clone(),
+          name.unwrap_or_else(|| field_name.clone()),
+        )
+      })
+      .collect();
+    let krate = match item.lit_str("crate") {
+      Some(_) => item.parse_str::<Path>("crate"),
+      None => pod_item.parse_str::<Path>("crate"),
+    };
+    let krate = errors
+      .check(krate)
+      .flatten()
+      .unwrap_or_else(|| parse_quote!(::podstru));
+    errors.finish()?;
+    Ok(Self {
+      krate,
+      item,
+      fields,
+      pod_item,
+      pod_fields,
+      names,
+    })
+  }
+
+  pub(crate) fn field(&self, field: &Ident) -> &Args {
+    &self.fields[field]
+  }
+
+  /// Whether `key` is set on the field, by the derive or by `pod`.
+  pub(crate) fn field_flag(&self, field: &Ident, key: &str) -> bool {
+    [self.field(field), &self.pod_fields[field]]
+      .iter()
+      .any(|args| args.flag(key).is_some())
+  }
+
+  /// Whether `key` is set on the field or on the struct, by the derive or by
+  /// `pod`.
+  pub(crate) fn inherited_flag(&self, field: &Ident, key: &str) -> bool {
+    self.field_flag(field, key)
+      || [&self.item, &self.pod_item]
+        .iter()
+        .any(|args| args.flag(key).is_some())
+  }
+
+  /// Path of the items generated code relies on, re-exported by `podstru`.
+  pub(crate) fn private(&self) -> TokenStream {
+    let krate = &self.krate;
+    quote! { #krate::__private }
+  }
+
+  /// Base name of the methods generated for `field`.
+  pub(crate) fn name(&self, field: &Ident) -> &Ident {
+    &self.names[field]
+  }
+}
+
+fn check_kind(meta: &Meta, key: &Key, ns: &Namespace) -> syn::Result<()> {
+  let prefix = format!("`{}` attribute on {}", key.name, ns.owner);
+  let expected = match (&key.kind, meta) {
+    (Kind::Flag, Meta::Path(_)) => return Ok(()),
+    (Kind::Flag, _) => "cannot have a value",
+    (Kind::Expr, Meta::NameValue(_)) => return Ok(()),
+    (Kind::Expr, _) => "must have a value",
+    (Kind::Str, Meta::NameValue(meta_name_value)) => match &meta_name_value.value {
+      Expr::Lit(ExprLit {
+        lit: Lit::Str(_), ..
+      }) => return Ok(()),
+      value => {
+        return Err(syn::Error::new(
+          value.span(),
+          format!("{} expects a string literal{}", prefix, key.hint),
+        ))
+      }
+    },
+    (Kind::Str, _) => "expects a string literal",
+    (Kind::Path, Meta::NameValue(meta_name_value)) => match &meta_name_value.value {
+      Expr::Path(path) if path.qself.is_none() => return Ok(()),
+      Expr::Lit(ExprLit {
+        lit: Lit::Str(lit), ..
+      }) => return lit.parse::<Path>().map(|_| ()),
+      value => {
+        return Err(syn::Error::new(
+          value.span(),
+          format!("{} expects a function path{}", prefix, key.hint),
+        ))
+      }
+    },
+    (Kind::Path, _) => "expects a function path",
+    (Kind::List, Meta::List(_)) => return Ok(()),
+    (Kind::List, _) => "expects a list",
+  };
+  Err(syn::Error::new(
+    meta.span(),
+    format!("{} {}{}", prefix, expected, key.hint),
+  ))
+}
+
+fn unknown_key(
+  meta: &Meta,
+  name: Option<String>,
+  ns: &Namespace,
+  keys: &[Key],
+  (other_keys, other_level): (&[Key], &str),
+) -> syn::Error {
+  let span = meta.path().span();
+  let Some(name) = name else {
+    return syn::Error::new(span, format!("unknown `{}` attribute", ns.attr));
+  };
+  if other_keys.iter().any(|key| key.name == name) {
+    return syn::Error::new(
+      span,
+      format!("`{}` attribute is only allowed on {}", name, other_level),
+    );
+  }
+  let mut message = format!("unknown `{}` attribute `{}`", ns.attr, name);
+  let closest = keys
+    .iter()
+    .map(|key| (distance(&name, key.name), key.name))
+    .filter(|(distance, key)| *distance <= (key.len() / 3).max(1))
+    .min();
+  match closest {
+    Some((_, key)) => message.push_str(&format!(", did you mean `{}`?", key)),
+    None if keys.is_empty() => message.push_str(", it takes no arguments here"),
+    None => {
+      let expected = keys
+        .iter()
+        .map(|key| format!("`{}`", key.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+      message.push_str(&format!(", expected one of {}", expected));
+    }
+  }
+  syn::Error::new(span, message)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+  let b = b.chars().collect::<Vec<_>>();
+  let mut row = (0..=b.len()).collect::<Vec<_>>();
+  for (i, ca) in a.chars().enumerate() {
+    let mut prev = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let cur = row[j + 1];
+      row[j + 1] = if ca == *cb {
+        prev
+      } else {
+        1 + prev.min(row[j]).min(cur)
+      };
+      prev = cur;
+    }
+  }
+  row[b.len()]
+}
+
+/// Whether `#[pod(key)]` is set on the field, before any derive parses it.
+pub(crate) fn pod_field_flag(field: &Field, key: &str) -> bool {
+  field
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("pod"))
+    .filter_map(|attr| parse_metas(attr).ok())
+    .flatten()
+    .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident(key)))
+}
+
+/// The default value of a field, given by `#[default(...)]` or by
+/// `#[builder(default = ...)]`, so that `PodDefault` and `Builder` agree.
+pub(crate) fn field_default(field: &Field) -> syn::Result<Option<Expr>> {
+  let mut defaults = vec![];
+  for attr in &field.attrs {
+    if attr.path().is_ident("default") {
+      match &attr.meta {
+        Meta::List(list) => defaults.push(list.parse_args::<Expr>()?),
+        _ => {
+          return Err(syn::Error::new_spanned(
+            attr,
+            "expected the default value, e.g. `#[default(42)]`",
+          ))
+        }
+      }
+    } else if attr.path().is_ident("builder") {
+      // Invalid arguments are reported by the `Builder` derive
+      let metas = parse_metas(attr).unwrap_or_default();
+      defaults.extend(metas.into_iter().filter_map(|meta| match meta {
+        Meta::NameValue(meta) if meta.path.is_ident("default") => Some(meta.value),
+        _ => None,
+      }));
+    }
+  }
+  match defaults.len() {
+    0 | 1 => Ok(defaults.pop()),
+    _ => Err(syn::Error::new_spanned(
+      &defaults[1],
+      "the default value of this field is already given, keep a single `#[default(...)]`",
+    )),
+  }
+}
+
+/// Parse the arguments of a helper attribute. Unlike `Meta`, keywords are
+/// accepted as keys, e.g. `#[ctor(const)]`.
+fn parse_metas(attr: &Attribute) -> syn::Result<Vec<Meta>> {
+  let mut metas = vec![];
+  attr.parse_nested_meta(|meta| {
+    let path = meta.path;
+    let input = meta.input;
+    if input.peek(Token![=]) {
+      metas.push(Meta::NameValue(MetaNameValue {
+        path,
+        eq_token: input.parse()?,
+        value: input.parse()?,
+      }));
+    } else if input.peek(token::Paren) {
+      let content;
+      let paren = parenthesized!(content in input);
+      metas.push(Meta::List(MetaList {
+        path,
+        delimiter: MacroDelimiter::Paren(paren),
+        tokens: content.parse()?,
+      }));
+    } else {
+      metas.push(Meta::Path(path));
+    }
+    Ok(())
+  })?;
+  Ok(metas)
+}
+
+/// Visibility of the generated methods: `vis = "..."` on a field, then on the
+/// struct, and the visibility of the field itself otherwise. At each level, the
+/// derive attribute takes precedence over `pod`.
+pub(crate) struct MethodsVis {
+  /// Visibility of the methods that are not tied to a single field.
+  pub(crate) item: Visibility,
+  fields: HashMap<Ident, Visibility>,
+}
+
+impl MethodsVis {
+  pub(crate) fn new(
+    attrs: &DeriveAttrs,
+    vis: &Visibility,
+    fields: &FieldsNamed,
+  ) -> syn::Result<Self> {
+    let mut errors = Errors::default();
+    let mut find_vis = |levels: [&Args; 2]| -> Option<Visibility> {
+      levels
+        .iter()
+        .find_map(|args| errors.check(args.parse_str("vis")).flatten())
+    };
+    let struct_vis = find_vis([&attrs.item, &attrs.pod_item]);
+    let fields = fields
+      .named
+      .iter()
+      .map(|f| {
+        let field_name = f.ident.clone().unwrap();
+        let field_vis = find_vis([attrs.field(&field_name), &attrs.pod_fields[&field_name]])
+          .or_else(|| struct_vis.clone())
+          .unwrap_or_else(|| f.vis.clone());
+        (field_name, field_vis)
+      })
+      .collect();
+    errors.finish()?;
+    Ok(Self {
+      item: struct_vis.unwrap_or_else(|| vis.clone()),
+      fields,
+    })
+  }
+
+  pub(crate) fn field(&self, field: &Ident) -> &Visibility {
+    &self.fields[field]
+  }
+}
+
+/// Attributes forwarded from a field onto the items generated for it.
+pub(crate) struct ForwardedAttrs {
+  /// The `///` docs of the field.
+  docs: Vec<Attribute>,
+  /// The `cfg` attributes, required wherever the field is mentioned.
+  cfgs: Vec<Attribute>,
+  /// The `deprecated` attributes and the `attr(...)` passthrough.
+  extra: TokenStream,
+}
+
+impl ForwardedAttrs {
+  pub(crate) fn new(
+    attrs: &DeriveAttrs,
+    fields: &FieldsNamed,
+  ) -> syn::Result<HashMap<Ident, Self>> {
+    let mut errors = Errors::default();
+    let struct_passthrough = passthrough(&attrs.item, &mut errors);
+    let forwarded = fields
+      .named
+      .iter()
+      .map(|f| {
+        let field_name = f.ident.clone().unwrap();
+        let filter = |name: &str| -> Vec<Attribute> {
+          f.attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(name))
+            .cloned()
+            .collect()
+        };
+        let deprecated = filter("deprecated");
+        let field_passthrough = passthrough(attrs.field(&field_name), &mut errors);
+        let forwarded = Self {
+          docs: filter("doc"),
+          cfgs: filter("cfg"),
+          extra: quote! {
+            #(#deprecated)*
+            #struct_passthrough
+            #field_passthrough
+          },
+        };
+        (field_name, forwarded)
+      })
+      .collect();
+    errors.finish()?;
+    Ok(forwarded)
+  }
+
+  /// Attributes of a generated method, documented by the field docs or else
+  /// by `default_doc`.
+  pub(crate) fn method(&self, default_doc: TokenStream) -> TokenStream {
+    let docs = match self.docs.is_empty() {
+      true => default_doc,
+      false => {
+        let docs = &self.docs;
+        quote! { #(#docs)* }
+      }
+    };
+    let cfgs = &self.cfgs;
+    let extra = &self.extra;
+    quote! {
+      #docs
+      #(#cfgs)*
+      #extra
+    }
+  }
+
+  /// The `cfg` attributes alone, for struct fields and parameters.
+  pub(crate) fn cfg(&self) -> TokenStream {
+    let cfgs = &self.cfgs;
+    quote! { #(#cfgs)* }
+  }
+}
+
+/// The `attr(...)` passthrough as outer attributes.
+fn passthrough(args: &Args, errors: &mut Errors) -> TokenStream {
+  args
+    .lists("attr")
+    .filter_map(|list| {
+      errors.check(list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated))
+    })
+    .flatten()
+    .map(|meta| quote! { #[#meta] })
+    .collect()
+}
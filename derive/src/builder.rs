@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Expr, Ident};
+
+use crate::{
+  attrs::{self, field_default, DeriveAttrs, Errors, ForwardedAttrs, MethodsVis},
+  names::{check_collisions, MethodNames},
+  utils::{is_option, option_inner, setter_arg, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+  let fname = format!("{}Builder", in_name);
+  let builder_ty = Ident::new(&fname, in_name.span());
+
+  let orig_fields = validate_struct(input, "Builder")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::BUILDER)?;
+  let methods_vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let mut errors = Errors::default();
+  let defaults: HashMap<&Ident, Option<Expr>> = orig_fields
+    .named
+    .iter()
+    .map(|f| {
+      (
+        f.ident.as_ref().unwrap(),
+        errors.check(field_default(f)).flatten(),
+      )
+    })
+    .collect();
+  errors.finish()?;
+
+  let field_skips: Vec<&Ident> = orig_fields
+    .named
+    .iter()
+    .filter_map(|f| f.ident.as_ref())
+    .filter(|field_name| attrs.field_flag(field_name, "skip"))
+    .collect();
+  let built_fields = || {
+    orig_fields
+      .named
+      .iter()
+      .filter(|f| !field_skips.contains(&f.ident.as_ref().unwrap()))
+  };
+
+  let method_names = built_fields().flat_map(|f| {
+    let field_name = f.ident.clone().unwrap();
+    let names = MethodNames::new(attrs.name(&field_name), MethodNames::BUILDER_RESERVED);
+    [names.get, names.get_mut, names.set, names.with].map(|name| (name, field_name.clone()))
+  });
+  check_collisions(method_names)?;
+
+  let field_accessors = built_fields()
+    .map(|f| {
+      let field_name = f.ident.clone().unwrap();
+      let field_ty = &f.ty;
+      let vis = methods_vis.field(&field_name);
+      let method_attrs = forwarded_attrs[&field_name].method(quote! {});
+      let MethodNames {
+        get: ref_func_name,
+        get_mut: ref_mut_func_name,
+        set: set_func_name,
+        with: with_func_name,
+        ..
+      } = MethodNames::new(attrs.name(&field_name), MethodNames::BUILDER_RESERVED);
+      let field_ty = match option_inner(field_ty) {
+        Some(ty) => quote! {#ty},
+        None => quote! {#field_ty},
+      };
+      let (arg_ty, arg) = setter_arg(&field_ty, attrs.inherited_flag(&field_name, "into"));
+      quote! {
+        #method_attrs
+        #vis fn #with_func_name(mut self, v: #arg_ty) -> Self {
+          self.#field_name = ::core::option::Option::Some(#arg);
+          self
+        }
+
+        #method_attrs
+        #vis fn #ref_func_name(&self) -> ::core::option::Option<&#field_ty> {
+          ::core::option::Option::as_ref(&self.#field_name)
+        }
+
+        #method_attrs
+        #vis fn #ref_mut_func_name(&mut self) -> &mut ::core::option::Option<#field_ty> {
+          &mut self.#field_name
+        }
+
+        #method_attrs
+        #vis fn #set_func_name(&mut self, v: #arg_ty) -> &mut Self {
+          self.#field_name = ::core::option::Option::Some(#arg);
+          self
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let new_fields = built_fields()
+    .map(|field| {
+      let field_name = field.ident.clone().unwrap();
+      let field_vis = field.vis.clone();
+      let field_ty = field.ty.clone();
+      let cfg = forwarded_attrs[&field_name].cfg();
+      if is_option(&field_ty) {
+        quote! {
+          #cfg
+          #field_vis #field_name: #field_ty,
+        }
+      } else {
+        quote! {
+          #cfg
+          #field_vis #field_name: ::core::option::Option<#field_ty>,
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let builder_ctor: TokenStream = built_fields()
+    .map(|field| {
+      let field_name = field.ident.clone().unwrap();
+      let cfg = forwarded_attrs[&field_name].cfg();
+      quote! {
+        #cfg
+        #field_name: ::core::default::Default::default(),
+      }
+    })
+    .collect();
+  let builder_ctor: TokenStream = quote! {
+    #builder_ty {
+      #builder_ctor
+    }
+  };
+
+  let orig_ctor: TokenStream = orig_fields
+    .named
+    .iter()
+    .map(|field| {
+      let field_name = field.ident.clone().unwrap();
+      let cfg = forwarded_attrs[&field_name].cfg();
+      let default = defaults[&field_name].as_ref();
+      // Skipped fields are not part of the builder
+      let field_value = match default {
+        Some(value) if field_skips.contains(&&field_name) => quote! { #value },
+        None if field_skips.contains(&&field_name) => quote! { ::core::default::Default::default() },
+        Some(value) if is_option(&field.ty) => quote! {
+          ::core::option::Option::Some(::core::option::Option::unwrap_or_else(self.#field_name, || #value))
+        },
+        Some(value) => quote! {
+          ::core::option::Option::unwrap_or_else(self.#field_name, || #value)
+        },
+        None if is_option(&field.ty) => quote! {
+          self.#field_name
+        },
+        None => quote! {
+          ::core::option::Option::unwrap_or_default(self.#field_name)
+        },
+      };
+      quote! {
+        #cfg
+        #field_name: #field_value,
+      }
+    })
+    .collect();
+  let orig_ctor: TokenStream = quote! {
+    #in_name {
+      #orig_ctor
+    }
+  };
+
+  let struct_vis = &input.vis;
+  let build_vis = &methods_vis.item;
+  let private = attrs.private();
+
+  Ok(quote! {
+    #struct_vis struct #builder_ty {
+      #new_fields
+    }
+
+    #[allow(deprecated)]
+    impl ::core::default::Default for #builder_ty {
+      fn default() -> Self {
+        #builder_ctor
+      }
+    }
+
+    impl #private::Builder for #in_name {
+      type Target = #builder_ty;
+
+      fn builder() -> Self::Target {
+        <Self::Target as ::core::default::Default>::default()
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #builder_ty {
+      #field_accessors
+
+      #build_vis fn build(mut self) -> #in_name {
+        #orig_ctor
+      }
+    }
+  })
+}
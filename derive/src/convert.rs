@@ -0,0 +1,162 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{spanned::Spanned, DeriveInput, Ident, Path, Type};
+
+use crate::{
+  attrs::{self, DeriveAttrs, Errors, ForwardedAttrs},
+  utils::validate_struct,
+};
+
+/// How a field maps onto its counterpart in the other struct.
+struct Mapping<'a> {
+  field_name: &'a Ident,
+  /// Name of the counterpart, from `rename`.
+  other: Ident,
+  /// Conversion into the counterpart, from `into_with` or `with`.
+  into_with: Option<Path>,
+  /// Conversion from the counterpart, from `from_with` or `with`.
+  from_with: Option<Path>,
+  /// The value of a skipped field, which has no counterpart.
+  skip: Option<TokenStream>,
+  cfg: TokenStream,
+}
+
+/// The converted value of `value.#field`, through `with` if any.
+fn convert(with: Option<&Path>, field: &Ident) -> TokenStream {
+  match with {
+    Some(with) => quote! { #with(value.#field) },
+    None => quote! { ::core::convert::Into::into(value.#field) },
+  }
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Convert")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::CONVERT)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let mut errors = Errors::default();
+  let mut targets = |key: &str| -> Vec<Type> {
+    attrs
+      .item
+      .split()
+      .filter_map(|args| errors.check(args.parse_str::<Type>(key)).flatten())
+      .collect()
+  };
+  let into = targets("into");
+  let from = targets("from");
+  if into.is_empty() && from.is_empty() {
+    errors.push(syn::Error::new(
+      Span::call_site(),
+      "`Convert` needs a type to convert to or from, e.g. `#[convert(into = \"DomainUser\")]`",
+    ));
+  }
+
+  let mappings = orig_fields
+    .named
+    .iter()
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let args = attrs.field(field_name);
+      let default = args.expr("default");
+      let with = errors.check(args.path("with")).flatten();
+      // A single function rarely converts both ways
+      if with.is_some() && !into.is_empty() && !from.is_empty() {
+        errors.push(syn::Error::new(
+          args.span("with").unwrap(),
+          "`with` would convert both into and from this struct, use `into_with` and `from_with` instead",
+        ));
+      }
+      // `#[pod(skip)]` only hides the field from the accessors
+      let skip = match args.flag("skip").is_some() {
+        true => Some(match default {
+          Some(value) => quote! { #value },
+          None => quote! { ::core::default::Default::default() },
+        }),
+        false => {
+          if default.is_some() {
+            errors.push(syn::Error::new(
+              args.span("default").unwrap(),
+              "`default` only applies to skipped fields, add `skip`",
+            ));
+          }
+          None
+        }
+      };
+      Mapping {
+        field_name,
+        other: errors
+          .check(args.parse_str::<Ident>("rename"))
+          .flatten()
+          .unwrap_or_else(|| field_name.clone()),
+        into_with: errors
+          .check(args.path("into_with"))
+          .flatten()
+          .or_else(|| with.clone()),
+        from_with: errors.check(args.path("from_with")).flatten().or(with),
+        skip,
+        cfg: forwarded_attrs[field_name].cfg(),
+      }
+    })
+    .collect::<Vec<_>>();
+  errors.finish()?;
+
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  // Mismatched fields are reported by the compiler, pointing at them: missing
+  // ones on the `into` type, unknown ones on either side
+  let into_impls = into.iter().map(|target| {
+    let fields = mappings
+      .iter()
+      .filter(|mapping| mapping.skip.is_none())
+      .map(|mapping| {
+        let Mapping { other, cfg, .. } = mapping;
+        let value = convert(mapping.into_with.as_ref(), mapping.field_name);
+        quote! {
+          #cfg
+          #other: #value,
+        }
+      });
+    let target_ctor = quote_spanned! { target.span()=> Self };
+    quote! {
+      #[allow(deprecated)]
+      impl #impl_generics ::core::convert::From<#in_name #ty_generics> for #target #where_clause {
+        fn from(value: #in_name #ty_generics) -> Self {
+          #target_ctor {
+            #(#fields)*
+          }
+        }
+      }
+    }
+  });
+  let from_impls = from.iter().map(|source| {
+    let fields = mappings.iter().map(|mapping| {
+      let Mapping {
+        field_name, cfg, ..
+      } = mapping;
+      let value = match &mapping.skip {
+        Some(value) => value.clone(),
+        None => convert(mapping.from_with.as_ref(), &mapping.other),
+      };
+      quote! {
+        #cfg
+        #field_name: #value,
+      }
+    });
+    quote! {
+      #[allow(deprecated)]
+      impl #impl_generics ::core::convert::From<#source> for #in_name #ty_generics #where_clause {
+        fn from(value: #source) -> Self {
+          Self {
+            #(#fields)*
+          }
+        }
+      }
+    }
+  });
+
+  Ok(quote! {
+    #(#into_impls)*
+    #(#from_impls)*
+  })
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use syn::{
+  ext::IdentExt, punctuated::Punctuated, spanned::Spanned, DeriveInput, FieldsNamed, Ident, Path,
+  Type,
+};
+
+use crate::{
+  attrs::{self, Args, DeriveAttrs, Errors, ForwardedAttrs, MethodsVis},
+  utils::validate_struct,
+};
+
+/// Constructors generated on the struct, along with the struct itself.
+pub(crate) fn methods(input: &DeriveInput) -> syn::Result<Vec<(Ident, Ident)>> {
+  let orig_fields = validate_struct(input, "Ctor")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::CTOR)?;
+  let mut names: Vec<Ident> = NamedCtor::new(&attrs.item, orig_fields)?
+    .into_iter()
+    .map(|ctor| ctor.name)
+    .collect();
+  if !names.iter().any(|name| name == "new") {
+    names.push(Ident::new("new", input.ident.span()));
+  }
+  let fallible = Validation::new(&attrs, orig_fields)?.is_some();
+  Ok(
+    names
+      .iter()
+      .map(|name| match fallible {
+        true => (try_name(name), input.ident.clone()),
+        false => (name.clone(), input.ident.clone()),
+      })
+      .collect(),
+  )
+}
+
+/// Name of the fallible version of the `name` constructor.
+fn try_name(name: &Ident) -> Ident {
+  Ident::new(&format!("try_{}", name.unraw()), name.span())
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_ty = &input.ident;
+
+  let orig_fields = validate_struct(input, "Ctor")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::CTOR)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?.item;
+
+  let mut field_skips: HashMap<Ident, TokenStream> = HashMap::new();
+  let mut field_defaults: Vec<&Ident> = vec![];
+  for f in &orig_fields.named {
+    let field_name = f.ident.as_ref().unwrap();
+    let field_attrs = attrs.field(field_name);
+    if let Some(field_value) = field_attrs.expr("skip") {
+      field_skips.insert(field_name.clone(), quote! { #field_value });
+    } else if field_attrs.flag("default").is_some() || attrs::pod_field_flag(f, "skip") {
+      // `#[pod(skip)]` leaves the field out of the parameters, as `default` does
+      field_skips.insert(
+        field_name.clone(),
+        quote! { ::core::default::Default::default() },
+      );
+      field_defaults.push(field_name);
+    }
+  }
+
+  let const_ctor = attrs.item.flag("const").is_some();
+
+  let mut named_ctors = NamedCtor::new(&attrs.item, orig_fields)?;
+  if !named_ctors.iter().any(|ctor| ctor.name == "new") {
+    named_ctors.insert(
+      0,
+      NamedCtor {
+        name: Ident::new("new", in_ty.span()),
+        params: orig_fields
+          .named
+          .iter()
+          .filter_map(|f| f.ident.clone())
+          .filter(|field_name| !field_skips.contains_key(field_name))
+          .collect(),
+      },
+    );
+  }
+
+  let validation = Validation::new(&attrs, orig_fields)?;
+  if const_ctor {
+    // `Default::default()` cannot be called from a `const fn`
+    let mut errors = Errors::default();
+    for ctor in &named_ctors {
+      for field_name in orig_fields.named.iter().filter_map(|f| f.ident.as_ref()) {
+        let defaulted =
+          field_defaults.contains(&field_name) || !field_skips.contains_key(field_name);
+        if defaulted && !ctor.params.contains(field_name) {
+          errors.push(syn::Error::new(
+            field_name.span(),
+            format!(
+              "`{}` defaults to `Default::default()` in `const fn {}`, which is not const-evaluable: use `#[ctor(skip = <const expr>)]` instead",
+              field_name, ctor.name
+            ),
+          ));
+        }
+      }
+    }
+    for field_name in orig_fields.named.iter().filter_map(|f| f.ident.as_ref()) {
+      if attrs.inherited_flag(field_name, "into") {
+        errors.push(syn::Error::new(
+          field_name.span(),
+          format!(
+            "`{}` is converted with `Into::into`, which cannot be called from a `const` constructor",
+            field_name
+          ),
+        ));
+      }
+    }
+    if let Some(validation) = &validation {
+      errors.push(syn::Error::new(
+        validation.span,
+        "`const` constructors cannot be fallible",
+      ));
+    }
+    errors.finish()?;
+  }
+  let constness = const_ctor.then(|| quote! { const });
+
+  let ctors = named_ctors
+    .iter()
+    .map(|ctor| {
+      let name = &ctor.name;
+      let params: TokenStream = ctor
+        .params
+        .iter()
+        .map(|field_name| {
+          let field = orig_fields
+            .named
+            .iter()
+            .find(|f| f.ident.as_ref() == Some(field_name))
+            .unwrap();
+          let field_ty = &field.ty;
+          let cfg = forwarded_attrs[field_name].cfg();
+          let param = attrs.name(field_name);
+          match attrs.inherited_flag(field_name, "into") {
+            true => quote! {
+              #cfg #param: impl ::core::convert::Into<#field_ty>,
+            },
+            false => quote! {
+              #cfg #param: #field_ty,
+            },
+          }
+        })
+        .collect();
+      // Parameters are named after `#[pod(rename)]`, and bound to the field
+      // names for the expressions of skipped fields
+      let conversions: TokenStream = orig_fields
+        .named
+        .iter()
+        .filter(|f| ctor.params.contains(f.ident.as_ref().unwrap()))
+        .map(|f| {
+          let field_name = f.ident.as_ref().unwrap();
+          let field_ty = &f.ty;
+          let cfg = forwarded_attrs[field_name].cfg();
+          let param = attrs.name(field_name);
+          match attrs.inherited_flag(field_name, "into") {
+            true => quote! {
+              #cfg let #field_name: #field_ty = ::core::convert::Into::into(#param);
+            },
+            false if param != field_name => quote! {
+              #cfg let #field_name = #param;
+            },
+            false => quote! {},
+          }
+        })
+        .collect();
+      // Skipped values are bound before the struct is assembled so that they
+      // may borrow the parameters.
+      let skipped_values: TokenStream = orig_fields
+        .named
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .filter(|field_name| !ctor.params.contains(field_name))
+        .map(|field_name| {
+          let cfg = forwarded_attrs[field_name].cfg();
+          let field_value = field_skips
+            .get(field_name)
+            .cloned()
+            .unwrap_or_else(|| quote! { ::core::default::Default::default() });
+          quote! {
+            #cfg let #field_name = #field_value;
+          }
+        })
+        .collect();
+      let orig_ctor: TokenStream = orig_fields
+        .named
+        .iter()
+        .map(|field| {
+          let field_name = field.ident.as_ref().unwrap();
+          let cfg = forwarded_attrs[field_name].cfg();
+          quote! {#cfg #field_name,}
+        })
+        .collect();
+      match &validation {
+        Some(validation) => {
+          let try_name = try_name(name);
+          let error = &validation.error;
+          let checks: TokenStream = validation
+            .checks
+            .iter()
+            .map(|(field_name, check)| {
+              let cfg = forwarded_attrs[field_name].cfg();
+              quote! {
+                #cfg #check(&#field_name)?;
+              }
+            })
+            .collect();
+          let validate = validation.validate.as_ref().map(|validate| {
+            quote! {
+              #validate(&value)?;
+            }
+          });
+          quote! {
+            #vis fn #try_name(#params) -> ::core::result::Result<Self, #error> {
+              #conversions
+              #skipped_values
+              #checks
+              let value = Self {
+                #orig_ctor
+              };
+              #validate
+              ::core::result::Result::Ok(value)
+            }
+          }
+        }
+        None => quote! {
+          #vis #constness fn #name(#params) -> Self {
+            #conversions
+            #skipped_values
+            Self {
+              #orig_ctor
+            }
+          }
+        },
+      }
+    })
+    .collect::<TokenStream>();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #in_ty {
+      #ctors
+    }
+  })
+}
+
+/// Validation requested through `#[ctor(validate = "...", error = "...")]` and
+/// `#[ctor(check = "...")]`, turning every constructor into a fallible `try_*`.
+struct Validation {
+  /// `fn(&Self) -> Result<(), E>` run once the struct is assembled.
+  validate: Option<Path>,
+  /// `fn(&T) -> Result<(), E>` run on a field value beforehand.
+  checks: Vec<(Ident, Path)>,
+  error: Type,
+  span: Span,
+}
+
+impl Validation {
+  fn new(attrs: &DeriveAttrs, fields: &FieldsNamed) -> syn::Result<Option<Self>> {
+    let mut errors = Errors::default();
+    let validate = errors.check(attrs.item.path("validate")).flatten();
+    let error = errors
+      .check(attrs.item.parse_str::<Type>("error"))
+      .flatten();
+    let mut span = attrs.item.span("validate");
+    let mut checks = vec![];
+    for field_name in fields.named.iter().filter_map(|f| f.ident.as_ref()) {
+      let field_attrs = attrs.field(field_name);
+      if let Some(check) = errors.check(field_attrs.path("check")).flatten() {
+        span = span.or_else(|| field_attrs.span("check"));
+        checks.push((field_name.clone(), check));
+      }
+    }
+    errors.finish()?;
+    match (span, error) {
+      (None, _) => Ok(None),
+      (Some(span), None) => Err(syn::Error::new(
+        span,
+        "fallible constructors require the error type: `#[ctor(error = \"MyError\")]`",
+      )),
+      (Some(span), Some(error)) => Ok(Some(Self {
+        validate,
+        checks,
+        error,
+        span,
+      })),
+    }
+  }
+}
+
+/// Additional constructor declared by `#[ctor(name = "with_id", fields(id, name))]`,
+/// taking the listed fields as parameters, in that order.
+struct NamedCtor {
+  name: Ident,
+  params: Vec<Ident>,
+}
+
+impl NamedCtor {
+  fn new(args: &Args, fields: &FieldsNamed) -> syn::Result<Vec<Self>> {
+    let mut errors = Errors::default();
+    let mut ctors: Vec<Self> = vec![];
+    for args in args.split() {
+      let name = errors.check(args.parse_str::<Ident>("name")).flatten();
+      let params = args.list("fields").and_then(|list| {
+        let idents =
+          errors.check(list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated))?;
+        for ident in &idents {
+          if !fields.named.iter().any(|f| f.ident.as_ref() == Some(ident)) {
+            errors.push(syn::Error::new(
+              ident.span(),
+              format!("unknown field `{}`", ident),
+            ));
+          } else if idents.iter().filter(|other| *other == ident).count() > 1 {
+            errors.push(syn::Error::new(
+              ident.span(),
+              format!("field `{}` is listed more than once", ident),
+            ));
+          }
+        }
+        Some(idents.into_iter().collect::<Vec<_>>())
+      });
+      match (name, params) {
+        (Some(name), Some(params)) => {
+          if ctors.iter().any(|ctor| ctor.name == name) {
+            errors.push(syn::Error::new(
+              name.span(),
+              format!("constructor `{}` is declared more than once", name),
+            ));
+          }
+          ctors.push(Self { name, params })
+        }
+        (Some(name), None) if args.list("fields").is_none() => errors.push(syn::Error::new(
+          name.span(),
+          "named constructors must list their parameters with `fields(...)`",
+        )),
+        (None, Some(_)) => errors.push(syn::Error::new(
+          args.list("fields").unwrap().span(),
+          "constructor parameters require a `name = \"...\"`",
+        )),
+        _ => {}
+      }
+    }
+    errors.finish()?;
+    Ok(ctors)
+  }
+}
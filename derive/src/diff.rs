@@ -0,0 +1,207 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs},
+  names::check_item_collisions,
+  utils::{bounded_generics, validate_struct, variant_name},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+  let vis = &input.vis;
+
+  let orig_fields = validate_struct(input, "Diff")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::DIFF)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+  let change_ty = attrs
+    .item
+    .parse_str::<Ident>("name")?
+    .unwrap_or_else(|| Ident::new(&format!("{}Change", in_name), in_name.span()));
+  let derives = attrs.item.lists("derive").map(|list| {
+    let tokens = &list.tokens;
+    quote! { #[derive(#tokens)] }
+  });
+
+  // (field, variant, nested)
+  let fields = orig_fields
+    .named
+    .iter()
+    .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let nested = attrs.field(field_name).flag("nested").is_some();
+      (f, variant_name(field_name), nested)
+    })
+    .collect::<Vec<_>>();
+  check_item_collisions(
+    fields
+      .iter()
+      .map(|(f, variant, _)| (variant.clone(), f.ident.clone().unwrap())),
+    "variant",
+  )?;
+
+  let variants = fields
+    .iter()
+    .map(|(f, variant, nested)| {
+      let field_name = f.ident.as_ref().unwrap();
+      let field_ty = &f.ty;
+      match nested {
+        true => {
+          let variant_attrs = forwarded_attrs[field_name].method(quote! {
+            #[doc = ::core::concat!("Changes within `", ::core::stringify!(#field_name), "`.")]
+          });
+          quote! {
+            #variant_attrs
+            #variant(#private::Vec<<#field_ty as #private::Diff>::Change>),
+          }
+        }
+        false => {
+          let variant_attrs = forwarded_attrs[field_name].method(quote! {
+            #[doc = ::core::concat!("`", ::core::stringify!(#field_name), "` changed from `old` to `new`.")]
+          });
+          quote! {
+            #variant_attrs
+            #variant {
+              old: #field_ty,
+              new: #field_ty,
+            },
+          }
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let diff = fields
+    .iter()
+    .map(|(f, variant, nested)| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      match nested {
+        true => quote! {
+          #cfg
+          {
+            let nested = #private::Diff::diff(&self.#field_name, &other.#field_name);
+            if !nested.is_empty() {
+              changes.push(#change_ty::#variant(nested));
+            }
+          }
+        },
+        false => quote! {
+          #cfg
+          if self.#field_name != other.#field_name {
+            changes.push(#change_ty::#variant {
+              old: ::core::clone::Clone::clone(&self.#field_name),
+              new: ::core::clone::Clone::clone(&other.#field_name),
+            });
+          }
+        },
+      }
+    })
+    .collect::<TokenStream>();
+
+  let apply = fields
+    .iter()
+    .map(|(f, variant, nested)| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      match nested {
+        true => quote! {
+          #cfg
+          #change_ty::#variant(ref changes) => {
+            #private::Diff::apply_changes(&mut self.#field_name, changes);
+          }
+        },
+        false => quote! {
+          #cfg
+          #change_ty::#variant { ref new, .. } => {
+            self.#field_name = ::core::clone::Clone::clone(new);
+          }
+        },
+      }
+    })
+    .collect::<TokenStream>();
+
+  // Written by hand, so that `#[pod(secret)]` values stay out of audit logs
+  let debug = fields
+    .iter()
+    .map(|(f, variant, nested)| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let name = variant.to_string();
+      match (nested, attrs.field_flag(field_name, "secret")) {
+        (true, _) => quote! {
+          #cfg
+          #change_ty::#variant(ref changes) => {
+            f.debug_tuple(#name).field(changes).finish()
+          }
+        },
+        (false, secret) => {
+          let (old, new) = match secret {
+            true => (
+              quote! { &#private::Secret::new(old) },
+              quote! { &#private::Secret::new(new) },
+            ),
+            false => (quote! { old }, quote! { new }),
+          };
+          quote! {
+            #cfg
+            #change_ty::#variant { ref old, ref new } => {
+              f.debug_struct(#name).field("old", #old).field("new", #new).finish()
+            }
+          }
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let generics = &input.generics;
+  let (_, ty_generics, where_clause) = generics.split_for_impl();
+  // Changed values are compared, then cloned into the records
+  let diff_generics = bounded_generics(
+    generics,
+    quote! { ::core::clone::Clone + ::core::cmp::PartialEq },
+  );
+  let (diff_impl_generics, _, diff_where_clause) = diff_generics.split_for_impl();
+  let debug_generics = bounded_generics(generics, quote! { ::core::fmt::Debug });
+  let (debug_impl_generics, _, debug_where_clause) = debug_generics.split_for_impl();
+
+  Ok(quote! {
+    #[doc = ::core::concat!("A change to one field of [`", ::core::stringify!(#in_name), "`].")]
+    #[derive(::core::clone::Clone, ::core::cmp::PartialEq)]
+    #(#derives)*
+    #vis enum #change_ty #generics #where_clause {
+      #variants
+    }
+
+    #[allow(deprecated)]
+    impl #debug_impl_generics ::core::fmt::Debug for #change_ty #ty_generics #debug_where_clause {
+      fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match *self {
+          #debug
+        }
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #diff_impl_generics #private::Diff for #in_name #ty_generics #diff_where_clause {
+      type Change = #change_ty #ty_generics;
+
+      fn diff(&self, other: &Self) -> #private::Vec<Self::Change> {
+        #[allow(unused_mut)]
+        let mut changes = #private::Vec::new();
+        #diff
+        changes
+      }
+
+      fn apply_changes(&mut self, changes: &[Self::Change]) {
+        for change in changes {
+          match *change {
+            #apply
+          }
+        }
+      }
+    }
+  })
+}
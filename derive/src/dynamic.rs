@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt, DeriveInput};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs},
+  utils::{bounded_generics, option_inner, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Dynamic")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::DYNAMIC)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+
+  let fields = orig_fields
+    .named
+    .iter()
+    .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"));
+  let getters = fields
+    .clone()
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let name = field_name.unraw().to_string();
+      quote! {
+        #cfg
+        #name => ::core::option::Option::Some(&self.#field_name),
+      }
+    })
+    .collect::<TokenStream>();
+  // Read-only fields are left out, as `get_field_mut` would bypass the guard
+  // of `set_field_str`
+  let getters_mut = fields
+    .clone()
+    .filter(|f| {
+      attrs
+        .field(f.ident.as_ref().unwrap())
+        .flag("read_only")
+        .is_none()
+    })
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let name = field_name.unraw().to_string();
+      quote! {
+        #cfg
+        #name => ::core::option::Option::Some(&mut self.#field_name),
+      }
+    })
+    .collect::<TokenStream>();
+  let setters = fields
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let name = field_name.unraw().to_string();
+      if attrs.field(field_name).flag("read_only").is_some() {
+        return quote! {
+          #cfg
+          #name => ::core::result::Result::Err(#private::FieldError::ReadOnly(#name)),
+        };
+      }
+      let parse = |ty: &dyn quote::ToTokens| {
+        quote! {
+          (&#private::ParseField::<#ty>::new(#name)).parse_field(value)?
+        }
+      };
+      let value = match option_inner(&f.ty) {
+        Some(inner) => {
+          let parse = parse(inner);
+          quote! {
+            match value.is_empty() {
+              true => ::core::option::Option::None,
+              false => ::core::option::Option::Some(#parse),
+            }
+          }
+        }
+        None => parse(&f.ty),
+      };
+      quote! {
+        #cfg
+        #name => {
+          self.#field_name = #value;
+          ::core::result::Result::Ok(())
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  // `dyn Any` requires `'static` types
+  let generics = bounded_generics(&input.generics, quote! { 'static });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics #private::Dynamic for #in_name #ty_generics #where_clause {
+      fn get_field(
+        &self,
+        name: &::core::primitive::str,
+      ) -> ::core::option::Option<&dyn ::core::any::Any> {
+        match name {
+          #getters
+          _ => ::core::option::Option::None,
+        }
+      }
+
+      fn get_field_mut(
+        &mut self,
+        name: &::core::primitive::str,
+      ) -> ::core::option::Option<&mut dyn ::core::any::Any> {
+        match name {
+          #getters_mut
+          _ => ::core::option::Option::None,
+        }
+      }
+
+      fn set_field_str(
+        &mut self,
+        name: &::core::primitive::str,
+        value: &::core::primitive::str,
+      ) -> ::core::result::Result<(), #private::FieldError> {
+        #[allow(unused_imports)]
+        use #private::{ParseFromStr as _, ParseUnsupported as _};
+        match name {
+          #setters
+          _ => ::core::result::Result::Err(#private::FieldError::unknown(name)),
+        }
+      }
+    }
+  })
+}
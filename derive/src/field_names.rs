@@ -0,0 +1,183 @@
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt, DeriveInput, Ident};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs},
+  names::check_item_collisions,
+  utils::{cfg_slice, validate_struct, variant_name},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+  let vis = &input.vis;
+
+  let orig_fields = validate_struct(input, "FieldNames")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::FIELD_NAMES)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+  let enum_name = attrs
+    .item
+    .parse_str::<Ident>("name")?
+    .unwrap_or_else(|| Ident::new(&format!("{}Field", in_name.unraw()), in_name.span()));
+
+  // (field, variant, name)
+  let fields = orig_fields
+    .named
+    .iter()
+    .filter_map(|f| f.ident.as_ref())
+    .filter(|field_name| !attrs.field_flag(field_name, "skip"))
+    .map(|field_name| {
+      let name = field_name.unraw().to_string();
+      let variant = variant_name(field_name);
+      (field_name, variant, name)
+    })
+    .collect::<Vec<_>>();
+  check_item_collisions(
+    fields
+      .iter()
+      .map(|(field_name, variant, _)| (variant.clone(), (*field_name).clone())),
+    "variant",
+  )?;
+
+  let variants = fields
+    .iter()
+    .map(|(field_name, variant, _)| {
+      let attrs = forwarded_attrs[*field_name].method(quote! {
+        #[doc = ::core::concat!("The `", ::core::stringify!(#field_name), "` field.")]
+      });
+      quote! {
+        #attrs
+        #variant,
+      }
+    })
+    .collect::<TokenStream>();
+  let as_str = fields
+    .iter()
+    .map(|(field_name, variant, name)| {
+      let cfg = forwarded_attrs[*field_name].cfg();
+      quote! {
+        #cfg
+        Self::#variant => #name,
+      }
+    })
+    .collect::<TokenStream>();
+  let from_str = fields
+    .iter()
+    .map(|(field_name, variant, name)| {
+      let cfg = forwarded_attrs[*field_name].cfg();
+      quote! {
+        #cfg
+        #name => ::core::result::Result::Ok(Self::#variant),
+      }
+    })
+    .collect::<TokenStream>();
+  let variant_items = fields
+    .iter()
+    .map(|(field_name, variant, _)| {
+      (
+        forwarded_attrs[*field_name].cfg(),
+        quote! { #enum_name::#variant },
+      )
+    })
+    .collect::<Vec<_>>();
+  let all = match variant_items.is_empty() {
+    true => quote! { &[] },
+    false => {
+      // Any variant that is compiled in can fill the array before it is set
+      let (cfgs, variants): (Vec<_>, Vec<_>) = variant_items.iter().cloned().unzip();
+      let placeholder = quote! {
+        {
+          let mut variant = ::core::option::Option::None;
+          #(
+            #cfgs
+            {
+              variant = ::core::option::Option::Some(#variants);
+            }
+          )*
+          match variant {
+            ::core::option::Option::Some(variant) => variant,
+            ::core::option::Option::None => ::core::panic!("every field is compiled out"),
+          }
+        }
+      };
+      cfg_slice(quote! { #enum_name }, placeholder, variant_items)
+    }
+  };
+  let names = cfg_slice(
+    quote! { &'static ::core::primitive::str },
+    quote! { "" },
+    fields
+      .iter()
+      .map(|(field_name, _, name)| (forwarded_attrs[*field_name].cfg(), quote! { #name }))
+      .collect(),
+  );
+
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  Ok(quote! {
+    #[doc = ::core::concat!("The fields of [`", ::core::stringify!(#in_name), "`].")]
+    #[derive(
+      ::core::fmt::Debug,
+      ::core::clone::Clone,
+      ::core::marker::Copy,
+      ::core::cmp::PartialEq,
+      ::core::cmp::Eq,
+      ::core::cmp::PartialOrd,
+      ::core::cmp::Ord,
+      ::core::hash::Hash,
+    )]
+    #vis enum #enum_name {
+      #variants
+    }
+
+    #[allow(deprecated)]
+    impl #enum_name {
+      /// Every field, in declaration order.
+      #vis const ALL: &'static [Self] = #all;
+
+      /// Name of the field.
+      #vis const fn as_str(&self) -> &'static ::core::primitive::str {
+        match *self {
+          #as_str
+        }
+      }
+    }
+
+    impl ::core::convert::From<#enum_name> for &'static ::core::primitive::str {
+      fn from(field: #enum_name) -> Self {
+        field.as_str()
+      }
+    }
+
+    impl ::core::fmt::Display for #enum_name {
+      fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(self.as_str())
+      }
+    }
+
+    #[allow(deprecated)]
+    impl ::core::str::FromStr for #enum_name {
+      type Err = #private::FieldError;
+
+      fn from_str(name: &::core::primitive::str) -> ::core::result::Result<Self, Self::Err> {
+        match name {
+          #from_str
+          _ => ::core::result::Result::Err(#private::FieldError::unknown(name)),
+        }
+      }
+    }
+
+    impl #impl_generics #private::FieldNames for #in_name #ty_generics #where_clause {
+      type Field = #enum_name;
+
+      const KEYS: &'static [Self::Field] = #enum_name::ALL;
+    }
+
+    impl #impl_generics #in_name #ty_generics #where_clause {
+      /// Names of the fields, in declaration order.
+      #vis const FIELD_NAMES: &'static [&'static ::core::primitive::str] = #names;
+      /// Number of fields.
+      #vis const FIELD_COUNT: ::core::primitive::usize = Self::FIELD_NAMES.len();
+    }
+  })
+}
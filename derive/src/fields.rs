@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs, MethodsVis},
+  getters::getter,
+  names::{check_collisions, MethodNames},
+  setters::{mutators, WithReceiver},
+  tracking::ChangeTracking,
+  utils::{is_option, option_inner, setter_arg, validate_struct},
+};
+
+/// Methods generated on the struct, along with the field they belong to.
+pub(crate) fn methods(input: &DeriveInput) -> syn::Result<Vec<(Ident, Ident)>> {
+  let orig_fields = validate_struct(input, "Fields")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::FIELDS)?;
+  let (tracking, field_skips) = ChangeTracking::with_skips(&attrs, orig_fields, "fields")?;
+  let mut methods = tracking
+    .map(|t| t.method_names(&input.ident))
+    .unwrap_or_default();
+  for f in &orig_fields.named {
+    let field_name = f.ident.as_ref().unwrap();
+    if !field_skips.contains(field_name) {
+      let names = MethodNames::new(attrs.name(field_name), MethodNames::RESERVED);
+      methods.push((names.get.clone(), field_name.clone()));
+      let setters = names.setters(is_option(&f.ty));
+      methods.extend(setters.into_iter().map(|name| (name, field_name.clone())));
+    }
+  }
+  Ok(methods)
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Fields")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::FIELDS)?;
+  let methods_vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let (tracking, field_skips) = ChangeTracking::with_skips(&attrs, orig_fields, "fields")?;
+  check_collisions(methods(input)?)?;
+  let with_receiver = WithReceiver::parse(&attrs)?;
+
+  let const_getters = attrs.item.flag("const").is_some();
+  let private = attrs.private();
+
+  let field_accessors = orig_fields
+    .named
+    .iter()
+    .filter(|f| !field_skips.contains(f.ident.as_ref().unwrap()))
+    .map(|f| {
+      let field_name = f.ident.clone().unwrap();
+      let field_ty = &f.ty;
+      let vis = methods_vis.field(&field_name);
+      let forwarded = &forwarded_attrs[&field_name];
+      let names = MethodNames::new(attrs.name(&field_name), MethodNames::RESERVED);
+      let MethodNames {
+        get: ref_func_name,
+        get_mut: ref_mut_func_name,
+        set: set_func_name,
+        with: with_func_name,
+        ..
+      } = &names;
+      let constness = (const_getters || attrs.field(&field_name).flag("const").is_some())
+        .then(|| quote! { const });
+      let mark_dirty = tracking.as_ref().map(|t| t.mark(&field_name));
+      let (arg_ty, arg) = setter_arg(field_ty, attrs.inherited_flag(&field_name, "into"));
+      let with_setter = with_receiver.setter(
+        with_func_name,
+        &field_name,
+        (&arg_ty, &arg),
+        tracking.as_ref(),
+        orig_fields,
+        &forwarded_attrs,
+      );
+      let mutators = mutators(
+        &names,
+        &field_name,
+        field_ty,
+        (&arg_ty, &arg),
+        mark_dirty.as_ref(),
+        vis,
+        |doc| forwarded.method(doc),
+      );
+
+      let (ref_mut_doc, ref_doc) = match option_inner(field_ty) {
+        Some(_) => (
+          quote! { #[doc = ::core::concat!("Return the `", ::core::stringify!(#field_name), "` field as a mutable reference.")] },
+          quote! { #[doc = ::core::concat!("Return the `", ::core::stringify!(#field_name), "` field.")] },
+        ),
+        None => (
+          quote! { #[doc = ::core::concat!("Retrieve the `", ::core::stringify!(#field_name), "` field as a mutable reference.")] },
+          quote! { #[doc = ::core::concat!("Retrieve the `", ::core::stringify!(#field_name), "` field as a reference.")] },
+        ),
+      };
+      let (ref_ty, ref_body) = getter(&field_name, field_ty, attrs.field_flag(&field_name, "secret"), &private);
+      let ref_mut_attrs = forwarded.method(ref_mut_doc);
+      let set_attrs = forwarded
+        .method(quote! { #[doc = ::core::concat!("Define the `", ::core::stringify!(#field_name), "` field.")] });
+      let ref_attrs = forwarded.method(ref_doc);
+      quote! {
+        #ref_mut_attrs
+        #vis fn #ref_mut_func_name(&mut self) -> &mut #field_ty {
+          #mark_dirty
+          &mut self.#field_name
+        }
+
+        #set_attrs
+        #vis fn #set_func_name(&mut self, v: #arg_ty) -> &mut Self {
+          #mark_dirty
+          self.#field_name = #arg;
+          self
+        }
+
+        #set_attrs
+        #vis #with_setter
+
+        #ref_attrs
+        #vis #constness fn #ref_func_name(&self) -> #ref_ty {
+          #ref_body
+        }
+
+        #mutators
+      }
+    })
+    .collect::<TokenStream>();
+  let tracking_methods = tracking
+    .as_ref()
+    .map(|t| t.methods(&methods_vis, &forwarded_attrs));
+  let tracking_check = tracking.as_ref().map(ChangeTracking::check);
+  let track_changes = tracking
+    .as_ref()
+    .map(|t| t.impl_track_changes(in_name, &private, &forwarded_attrs));
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #in_name {
+      #field_accessors
+      #tracking_methods
+    }
+
+    #track_changes
+    #tracking_check
+  })
+}
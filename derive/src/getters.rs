@@ -0,0 +1,105 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident, Type};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs, MethodsVis},
+  names::{check_collisions, MethodNames},
+  utils::{option_inner, validate_struct},
+};
+
+/// Methods generated on the struct, along with the field they belong to.
+pub(crate) fn methods(input: &DeriveInput) -> syn::Result<Vec<(Ident, Ident)>> {
+  let orig_fields = validate_struct(input, "Getters")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::GETTERS)?;
+  Ok(
+    orig_fields
+      .named
+      .iter()
+      .filter_map(|f| f.ident.clone())
+      .filter(|field_name| !attrs.field_flag(field_name, "skip"))
+      .map(|field_name| {
+        let getter = MethodNames::new(attrs.name(&field_name), MethodNames::RESERVED).get;
+        (getter, field_name)
+      })
+      .collect(),
+  )
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Getters")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::GETTERS)?;
+  let methods_vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  check_collisions(methods(input)?)?;
+
+  let const_getters = attrs.item.flag("const").is_some();
+  let private = attrs.private();
+
+  let field_accessors = orig_fields
+    .named
+    .iter()
+    .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+    .map(|f| {
+      let field_name = f.ident.clone().unwrap();
+      let field_ty = &f.ty;
+      let vis = methods_vis.field(&field_name);
+      let method_attrs = forwarded_attrs[&field_name].method(quote! {});
+      let ref_func_name = MethodNames::new(attrs.name(&field_name), MethodNames::RESERVED).get;
+      let constness = (const_getters || attrs.field(&field_name).flag("const").is_some())
+        .then(|| quote! { const });
+      let (ret_ty, body) = getter(
+        &field_name,
+        field_ty,
+        attrs.field_flag(&field_name, "secret"),
+        &private,
+      );
+      quote! {
+        #method_attrs
+        #vis #constness fn #ref_func_name(&self) -> #ret_ty {
+          #body
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #in_name {
+      #field_accessors
+    }
+  })
+}
+
+/// Return type and body of the getter of a field: `Option<&T>` for optional
+/// fields, wrapped in `Secret` for `#[pod(secret)]` ones, which keeps them out
+/// of `Debug` output.
+pub(crate) fn getter(
+  field_name: &Ident,
+  field_ty: &Type,
+  secret: bool,
+  private: &TokenStream,
+) -> (TokenStream, TokenStream) {
+  match (option_inner(field_ty), secret) {
+    (Some(unwrapped_field_ty), false) => (
+      quote! { ::core::option::Option<&#unwrapped_field_ty> },
+      quote! { ::core::option::Option::as_ref(&self.#field_name) },
+    ),
+    (Some(unwrapped_field_ty), true) => (
+      quote! { ::core::option::Option<#private::Secret<&#unwrapped_field_ty>> },
+      quote! {
+        match &self.#field_name {
+          ::core::option::Option::Some(value) => ::core::option::Option::Some(#private::Secret::new(value)),
+          ::core::option::Option::None => ::core::option::Option::None,
+        }
+      },
+    ),
+    (None, false) => (quote! { &#field_ty }, quote! { &self.#field_name }),
+    (None, true) => (
+      quote! { #private::Secret<&#field_ty> },
+      quote! { #private::Secret::new(&self.#field_name) },
+    ),
+  }
+}
@@ -0,0 +1,113 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Field, LitStr};
+
+use crate::{
+  attrs::{self, DeriveAttrs, Errors, ForwardedAttrs},
+  utils::{is_option, validate_struct},
+};
+
+/// How a field of `other` is merged into `self`.
+enum Strategy {
+  /// Replace the field.
+  Overwrite,
+  /// Leave the field untouched.
+  Keep,
+  /// Extend the field, a collection, with the other one.
+  Append,
+  /// Merge the field, itself implementing `Merge`.
+  Recurse,
+  /// Replace the field, an `Option`, unless the other one is `None`.
+  OverwriteIfSome,
+}
+
+impl Strategy {
+  const NAMES: &'static str = "`overwrite`, `keep`, `append`, `recurse` or `overwrite_if_some`";
+
+  fn new(field: &Field, strategy: Option<&LitStr>) -> syn::Result<Self> {
+    let Some(strategy) = strategy else {
+      // Overlays leave the options they do not set to `None`
+      return Ok(match is_option(&field.ty) {
+        true => Self::OverwriteIfSome,
+        false => Self::Overwrite,
+      });
+    };
+    match strategy.value().as_str() {
+      "overwrite" => Ok(Self::Overwrite),
+      "keep" => Ok(Self::Keep),
+      "append" => Ok(Self::Append),
+      "recurse" => Ok(Self::Recurse),
+      "overwrite_if_some" if is_option(&field.ty) => Ok(Self::OverwriteIfSome),
+      "overwrite_if_some" => Err(syn::Error::new_spanned(
+        strategy,
+        "`overwrite_if_some` only applies to `Option` fields",
+      )),
+      other => Err(syn::Error::new_spanned(
+        strategy,
+        format!(
+          "unknown merge strategy `{}`, expected one of {}",
+          other,
+          Self::NAMES
+        ),
+      )),
+    }
+  }
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Merge")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::MERGE)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+
+  let mut errors = Errors::default();
+  let merged_fields = orig_fields
+    .named
+    .iter()
+    .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+    .filter_map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let strategy = errors.check(Strategy::new(
+        f,
+        attrs.field(field_name).lit_str("strategy"),
+      ))?;
+      let cfg = forwarded_attrs[field_name].cfg();
+      let merge = match strategy {
+        Strategy::Overwrite => quote! {
+          self.#field_name = other.#field_name;
+        },
+        Strategy::Keep => return None,
+        Strategy::Append => quote! {
+          ::core::iter::Extend::extend(&mut self.#field_name, other.#field_name);
+        },
+        Strategy::Recurse => quote! {
+          #private::Merge::merge(&mut self.#field_name, other.#field_name);
+        },
+        Strategy::OverwriteIfSome => quote! {
+          if let ::core::option::Option::Some(value) = other.#field_name {
+            self.#field_name = ::core::option::Option::Some(value);
+          }
+        },
+      };
+      Some(quote! {
+        #cfg {
+          #merge
+        }
+      })
+    })
+    .collect::<TokenStream>();
+  errors.finish()?;
+
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics #private::Merge for #in_name #ty_generics #where_clause {
+      #[allow(unused_variables)]
+      fn merge(&mut self, other: Self) {
+        #merged_fields
+      }
+    }
+  })
+}
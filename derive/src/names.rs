@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use syn::{ext::IdentExt, Ident};
+
+use crate::attrs::Errors;
+
+/// Names of the methods generated for a field. Raw identifiers are stripped
+/// (`r#type` gives `with_type`), and getters which would otherwise be raw or
+/// shadow a `reserved` method get a trailing underscore (`type_()`).
+pub(crate) struct MethodNames {
+  pub(crate) get: Ident,
+  pub(crate) get_mut: Ident,
+  pub(crate) set: Ident,
+  pub(crate) with: Ident,
+  pub(crate) update: Ident,
+  pub(crate) replace: Ident,
+  /// Only generated for `Option` fields, as are `get_or_insert_with`.
+  pub(crate) take: Ident,
+  pub(crate) get_or_insert_with: Ident,
+}
+
+impl MethodNames {
+  /// Methods found on the struct itself, generated by the `Ctor` and
+  /// `Builder` derives or by `#[derive(Default)]`.
+  pub(crate) const RESERVED: &'static [&'static str] = &["new", "builder", "default"];
+  /// Methods found on the generated builder.
+  pub(crate) const BUILDER_RESERVED: &'static [&'static str] = &["build", "default"];
+
+  pub(crate) fn new(field: &Ident, reserved: &[&str]) -> Self {
+    let name = field.unraw().to_string();
+    let get = if *field != name || reserved.contains(&name.as_str()) {
+      format!("{}_", name)
+    } else {
+      name.clone()
+    };
+    Self {
+      get: Ident::new(&get, field.span()),
+      get_mut: Ident::new(&format!("{}_mut", name), field.span()),
+      set: Ident::new(&format!("set_{}", name), field.span()),
+      with: Ident::new(&format!("with_{}", name), field.span()),
+      update: Ident::new(&format!("update_{}", name), field.span()),
+      replace: Ident::new(&format!("replace_{}", name), field.span()),
+      take: Ident::new(&format!("take_{}", name), field.span()),
+      get_or_insert_with: Ident::new(&format!("get_or_insert_{}_with", name), field.span()),
+    }
+  }
+
+  /// The methods updating the field generated by `Setters` and `Fields`.
+  pub(crate) fn setters(self, optional: bool) -> Vec<Ident> {
+    let mut setters = vec![self.get_mut, self.set, self.with, self.update, self.replace];
+    if optional {
+      setters.extend([self.take, self.get_or_insert_with]);
+    }
+    setters
+  }
+}
+
+/// Reject methods generated more than once, e.g. `a_mut()` for both the `a`
+/// and `a_mut` fields of `Fields`, pointing at every offending field.
+pub(crate) fn check_collisions(
+  methods: impl IntoIterator<Item = (Ident, Ident)>,
+) -> syn::Result<()> {
+  check_item_collisions(methods, "method")
+}
+
+/// Same as [`check_collisions`], for generated items other than methods.
+pub(crate) fn check_item_collisions(
+  items: impl IntoIterator<Item = (Ident, Ident)>,
+  what: &str,
+) -> syn::Result<()> {
+  let mut seen: HashMap<String, Ident> = HashMap::new();
+  let mut errors = Errors::default();
+  for (item, owner) in items {
+    let name = item.to_string();
+    match seen.get(&name) {
+      Some(prev) => errors.push(syn::Error::new(
+        owner.span(),
+        format!(
+          "generated {} `{}` for `{}` collides with the one generated for `{}`",
+          what,
+          name,
+          owner.unraw(),
+          prev.unraw()
+        ),
+      )),
+      None => {
+        seen.insert(name, owner);
+      }
+    }
+  }
+  errors.finish()
+}
@@ -0,0 +1,141 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs, MethodsVis},
+  utils::{bounded_generics, is_option, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Partial")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::PARTIAL)?;
+  let methods_vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let partial_ty = attrs
+    .item
+    .parse_str::<Ident>("name")?
+    .unwrap_or_else(|| Ident::new(&format!("Partial{}", in_name), in_name.span()));
+  let derives = attrs.item.lists("derive").map(|list| {
+    let tokens = &list.tokens;
+    quote! { #[derive(#tokens)] }
+  });
+
+  let patched_fields = || {
+    orig_fields
+      .named
+      .iter()
+      .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+  };
+
+  let new_fields = patched_fields()
+    .map(|field| {
+      let field_name = field.ident.as_ref().unwrap();
+      let field_vis = methods_vis.field(field_name);
+      let field_ty = &field.ty;
+      // Optional fields need a third state, to tell clearing them from
+      // leaving them untouched
+      let field_attrs = forwarded_attrs[field_name].method(match is_option(field_ty) {
+        true => quote! {
+          #[doc = ::core::concat!(
+            "New `", ::core::stringify!(#field_name), "`: `Some(None)` clears it, `None` leaves it untouched."
+          )]
+        },
+        false => quote! {
+          #[doc = ::core::concat!("New `", ::core::stringify!(#field_name), "`, if any.")]
+        },
+      });
+      quote! {
+        #field_attrs
+        #field_vis #field_name: ::core::option::Option<#field_ty>,
+      }
+    })
+    .collect::<TokenStream>();
+
+  let empty = patched_fields()
+    .map(|field| {
+      let field_name = field.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      quote! {
+        #cfg
+        #field_name: ::core::option::Option::None,
+      }
+    })
+    .collect::<TokenStream>();
+
+  let apply = patched_fields()
+    .map(|field| {
+      let field_name = field.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      quote! {
+        #cfg
+        if let ::core::option::Option::Some(value) = patch.#field_name {
+          self.#field_name = value;
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let diff = patched_fields()
+    .map(|field| {
+      let field_name = field.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      quote! {
+        #cfg
+        #field_name: match self.#field_name == other.#field_name {
+          true => ::core::option::Option::None,
+          false => ::core::option::Option::Some(::core::clone::Clone::clone(&other.#field_name)),
+        },
+      }
+    })
+    .collect::<TokenStream>();
+
+  let struct_vis = &input.vis;
+  let vis = &methods_vis.item;
+  let generics = &input.generics;
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  // Fields are compared, then cloned into the patch
+  let diff_generics = bounded_generics(
+    generics,
+    quote! { ::core::clone::Clone + ::core::cmp::PartialEq },
+  );
+  let (diff_impl_generics, _, diff_where_clause) = diff_generics.split_for_impl();
+
+  Ok(quote! {
+    #[doc = ::core::concat!(
+      "A patch of [`", ::core::stringify!(#in_name), "`], only holding the fields to update."
+    )]
+    #(#derives)*
+    #struct_vis struct #partial_ty #generics #where_clause {
+      #new_fields
+    }
+
+    #[allow(deprecated)]
+    impl #impl_generics ::core::default::Default for #partial_ty #ty_generics #where_clause {
+      fn default() -> Self {
+        #partial_ty {
+          #empty
+        }
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #impl_generics #in_name #ty_generics #where_clause {
+      /// Overwrite the fields set in `patch`, leaving the others untouched.
+      #vis fn apply(&mut self, patch: #partial_ty #ty_generics) {
+        #apply
+      }
+    }
+
+    #[allow(deprecated)]
+    impl #diff_impl_generics #in_name #ty_generics #diff_where_clause {
+      /// The patch turning `self` into `other`, holding the fields which differ.
+      #vis fn diff_to_partial(&self, other: &Self) -> #partial_ty #ty_generics {
+        #partial_ty {
+          #diff
+        }
+      }
+    }
+  })
+}
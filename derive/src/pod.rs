@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use syn::{
+  ext::IdentExt, punctuated::Punctuated, Attribute, Data, DeriveInput, Field, Ident, Path,
+};
+
+use crate::{
+  attrs::{self, Args, Errors},
+  ctor, fields, getters, setters,
+};
+
+/// The derives selectable from `#[pod(...)]`, by key.
+const DERIVES: [(&str, &str); 18] = [
+  ("builder", "Builder"),
+  ("convert", "Convert"),
+  ("ctor", "Ctor"),
+  ("diff", "Diff"),
+  ("dynamic", "Dynamic"),
+  ("field_names", "FieldNames"),
+  ("fields", "Fields"),
+  ("getters", "Getters"),
+  ("merge", "Merge"),
+  ("partial", "Partial"),
+  ("pod_debug", "PodDebug"),
+  ("pod_default", "PodDefault"),
+  ("pod_display", "PodDisplay"),
+  ("pod_eq", "PodEq"),
+  ("pod_hash", "PodHash"),
+  ("pod_ord", "PodOrd"),
+  ("reflect", "Reflect"),
+  ("setters", "Setters"),
+];
+
+/// Standard derives applied along with the selected ones, by module.
+const STD_DERIVES: [(&str, &str); 4] = [
+  ("fmt", "Debug"),
+  ("clone", "Clone"),
+  ("cmp", "PartialEq"),
+  ("default", "Default"),
+];
+
+/// Derives standing in for a standard one, by key, which would silently
+/// ignore their field attributes.
+const REPLACEMENTS: [(&str, &str); 3] = [
+  ("pod_debug", "Debug"),
+  ("pod_default", "Default"),
+  ("pod_eq", "PartialEq"),
+];
+
+/// Derives selected along with another one, by key, which requires them.
+const IMPLIED: [(&str, &str); 1] = [("pod_ord", "pod_eq")];
+
+pub(crate) fn expand(args: TokenStream, input: &DeriveInput) -> syn::Result<TokenStream> {
+  let args = Args::parse_macro(args, &attrs::POD_MACRO)?;
+  let derived = derived(input)?;
+  let selected: Vec<(&str, &str)> = DERIVES
+    .into_iter()
+    .filter(|(key, _)| {
+      args.flag(key).is_some() || needed(key, input) || implied(key, &args, &derived)
+    })
+    .collect();
+
+  let mut errors = Errors::default();
+  if selected.is_empty() {
+    // Nothing would register the `pod` helper attribute
+    for key in ["vis", "into", "crate"] {
+      if let Some(span) = args.span(key) {
+        errors.push(syn::Error::new(
+          span,
+          format!(
+            "`{}` only applies to the podstru derives, none is selected",
+            key
+          ),
+        ));
+      }
+    }
+  }
+  if args.flag("fields").is_some() {
+    for key in ["getters", "setters"] {
+      if let Some(span) = args.span(key) {
+        errors.push(syn::Error::new(
+          span,
+          format!("`fields` already generates the {}, drop `{}`", key, key),
+        ));
+      }
+    }
+  }
+  errors.finish()?;
+  check_collisions(&selected, input)?;
+
+  let krate = args
+    .parse_str::<Path>("crate")?
+    .unwrap_or_else(|| parse_quote!(::podstru));
+  let derives = selected
+    .iter()
+    .map(|(_, derive)| {
+      let derive = Ident::new(derive, input.ident.span());
+      quote! { #krate::#derive }
+    })
+    .chain(
+      STD_DERIVES
+        .into_iter()
+        .filter(|(_, derive)| !derived.iter().any(|other| other == derive))
+        .filter(|(_, derive)| {
+          !REPLACEMENTS
+            .iter()
+            .any(|(key, std)| std == derive && selected.iter().any(|(other, _)| other == key))
+        })
+        .map(|(module, derive)| {
+          let module = Ident::new(module, input.ident.span());
+          let derive = Ident::new(derive, input.ident.span());
+          quote! { ::core::#module::#derive }
+        }),
+    );
+  let options = args
+    .lit_str("vis")
+    .map(|vis| quote! { vis = #vis })
+    .into_iter()
+    .chain(args.flag("into").map(|into| quote! { #into }))
+    .chain(args.lit_str("crate").map(|krate| quote! { crate = #krate }))
+    .collect::<Vec<_>>();
+  let options = (!options.is_empty()).then(|| quote! { #[pod(#(#options),*)] });
+
+  Ok(quote! {
+    #[derive(#(#derives),*)]
+    #options
+    #input
+  })
+}
+
+/// Whether a field has attributes only the derive of `key` handles, in place
+/// of the standard one.
+fn needed(key: &str, input: &DeriveInput) -> bool {
+  let Data::Struct(data) = &input.data else {
+    return false;
+  };
+  let has_attr = |f: &Field, name: &str| f.attrs.iter().any(|attr| attr.path().is_ident(name));
+  data.fields.iter().any(|f| match key {
+    "pod_debug" => has_attr(f, "debug") || attrs::pod_field_flag(f, "secret"),
+    "pod_default" => has_attr(f, "default"),
+    "pod_eq" => has_attr(f, "eq"),
+    _ => false,
+  })
+}
+
+/// Whether the derive of `key` is required by another selected one. `PodOrd`
+/// requires `Eq`, which only `PodEq` adds, unless `PartialEq` is derived
+/// already.
+fn implied(key: &str, args: &Args, derived: &[Ident]) -> bool {
+  IMPLIED
+    .iter()
+    .any(|(by, implied)| *implied == key && args.flag(by).is_some())
+    && !derived.iter().any(|derive| derive == "PartialEq")
+}
+
+/// Reject methods generated on the struct by more than one derive, such as a
+/// `with_id` constructor along with the `with_id` setter. Collisions within a
+/// derive, or invalid attributes, are left to the derive itself.
+fn check_collisions(selected: &[(&str, &str)], input: &DeriveInput) -> syn::Result<()> {
+  let mut seen: HashMap<String, (&str, Ident)> = HashMap::new();
+  let mut errors = Errors::default();
+  for (key, derive) in selected {
+    let methods = match *key {
+      "ctor" => ctor::methods(input),
+      "fields" => fields::methods(input),
+      "getters" => getters::methods(input),
+      "setters" => setters::methods(input),
+      _ => Ok(vec![]),
+    };
+    for (method, owner) in methods.unwrap_or_default() {
+      let name = method.to_string();
+      match seen.get(&name) {
+        Some((other, prev)) if other != derive => errors.push(syn::Error::new(
+          owner.span(),
+          format!(
+            "method `{}` generated by `{}` for `{}` collides with the one generated by `{}` for `{}`",
+            name,
+            derive,
+            owner.unraw(),
+            other,
+            prev.unraw()
+          ),
+        )),
+        Some(_) => {}
+        None => {
+          seen.insert(name, (derive, owner));
+        }
+      }
+    }
+  }
+  errors.finish()
+}
+
+/// Remove the helper attributes of every derive from the struct and its fields.
+pub(crate) fn strip_helpers(input: &mut DeriveInput) {
+  let is_helper = |attr: &Attribute| {
+    let path = attr.path();
+    ["pod", "debug", "default", "display", "eq", "hash", "ord"]
+      .iter()
+      .chain(DERIVES.iter().map(|(key, _)| key))
+      .any(|helper| path.is_ident(helper))
+  };
+  input.attrs.retain(|attr| !is_helper(attr));
+  if let Data::Struct(data) = &mut input.data {
+    for field in data.fields.iter_mut() {
+      field.attrs.retain(|attr| !is_helper(attr));
+    }
+  }
+}
+
+/// Names of the traits already derived on the struct.
+fn derived(input: &DeriveInput) -> syn::Result<Vec<Ident>> {
+  let mut derived = vec![];
+  for attr in input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("derive"))
+  {
+    let paths = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+    derived.extend(
+      paths
+        .into_iter()
+        .filter_map(|path| path.segments.last().map(|seg| seg.ident.clone())),
+    );
+  }
+  Ok(derived)
+}
@@ -0,0 +1,78 @@
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt, DeriveInput};
+
+use crate::{
+  attrs::{self, DeriveAttrs, Errors, ForwardedAttrs},
+  utils::{bounded_generics, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "PodDebug")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::DEBUG)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+
+  let mut errors = Errors::default();
+  let mut skipped = false;
+  let fields = orig_fields
+    .named
+    .iter()
+    .filter_map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      if attrs.field_flag(field_name, "skip") {
+        skipped = true;
+        return None;
+      }
+      let args = attrs.field(field_name);
+      let with = errors.check(args.path("with")).flatten();
+      let value = match (args.flag("redact"), with) {
+        (Some(redact), Some(_)) => {
+          errors.push(syn::Error::new_spanned(
+            redact,
+            "a redacted field is never formatted, drop `with`",
+          ));
+          return None;
+        }
+        // Called through a closure, for deref coercions: `&String` to `&str`
+        (None, Some(with)) => quote! {
+          &#private::DebugWith(&self.#field_name, |value, f| #with(value, f))
+        },
+        // A secret is redacted unless `with` masks it some other way
+        _ if args.flag("redact").is_some() || attrs.field_flag(field_name, "secret") => quote! {
+          &#private::Secret::new(&self.#field_name)
+        },
+        _ => quote! { &self.#field_name },
+      };
+      let cfg = forwarded_attrs[field_name].cfg();
+      let key = field_name.unraw().to_string();
+      Some(quote! {
+        #cfg
+        ::core::fmt::DebugStruct::field(&mut debug, #key, #value);
+      })
+    })
+    .collect::<TokenStream>();
+  errors.finish()?;
+
+  let finish = match skipped {
+    true => quote! { ::core::fmt::DebugStruct::finish_non_exhaustive(&mut debug) },
+    false => quote! { ::core::fmt::DebugStruct::finish(&mut debug) },
+  };
+  let name = in_name.unraw().to_string();
+
+  let generics = bounded_generics(&input.generics, quote! { ::core::fmt::Debug });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics ::core::fmt::Debug for #in_name #ty_generics #where_clause {
+      fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        #[allow(unused_mut)]
+        let mut debug = ::core::fmt::Formatter::debug_struct(f, #name);
+        #fields
+        #finish
+      }
+    }
+  })
+}
@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use syn::DeriveInput;
+
+use crate::{
+  attrs::{field_default, Errors},
+  utils::{bounded_generics, is_option, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "PodDefault")?;
+
+  let mut errors = Errors::default();
+  let fields = orig_fields
+    .named
+    .iter()
+    .map(|field| {
+      let field_name = field.ident.as_ref().unwrap();
+      let cfgs = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+      // As with the builder, the default of an `Option` is the inner value
+      let value = match errors.check(field_default(field)).flatten() {
+        Some(value) if is_option(&field.ty) => quote! { ::core::option::Option::Some(#value) },
+        Some(value) => quote! { #value },
+        None => quote! { ::core::default::Default::default() },
+      };
+      quote! {
+        #(#cfgs)*
+        #field_name: #value,
+      }
+    })
+    .collect::<TokenStream>();
+  errors.finish()?;
+
+  let generics = bounded_generics(&input.generics, quote! { ::core::default::Default });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics ::core::default::Default for #in_name #ty_generics #where_clause {
+      fn default() -> Self {
+        Self {
+          #fields
+        }
+      }
+    }
+  })
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt, DeriveInput, Ident, LitStr};
+
+use crate::{
+  attrs::{self, DeriveAttrs, Errors, ForwardedAttrs},
+  utils::{bounded_generics, is_option, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  // The template is a bare string, unlike the other `display` arguments
+  let (templates, attrs): (Vec<_>, Vec<_>) = input
+    .attrs
+    .iter()
+    .cloned()
+    .partition(|attr| attr.path().is_ident("display") && attr.parse_args::<LitStr>().is_ok());
+  let stripped = DeriveInput {
+    attrs,
+    ..input.clone()
+  };
+  let orig_fields = validate_struct(input, "PodDisplay")?;
+  let attrs = DeriveAttrs::parse(&stripped, orig_fields, &attrs::DISPLAY)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  if let Some(template) = templates.get(1) {
+    return Err(syn::Error::new_spanned(
+      template,
+      "duplicate `display` template",
+    ));
+  }
+
+  let mut errors = Errors::default();
+  // The value of each field, formatted by its `fmt`
+  let values: HashMap<&Ident, TokenStream> = orig_fields
+    .named
+    .iter()
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let value = match attrs.field(field_name).lit_str("fmt") {
+        Some(fmt) => {
+          errors.check(check_fmt(fmt));
+          quote! { ::core::format_args!(#fmt, self.#field_name) }
+        }
+        None => quote! { self.#field_name },
+      };
+      (field_name, value)
+    })
+    .collect();
+  errors.finish()?;
+
+  let body = match templates.first() {
+    Some(template) => {
+      let template = template.parse_args::<LitStr>()?;
+      let field_names = || orig_fields.named.iter().filter_map(|f| f.ident.as_ref());
+      let (format, args) = parse_template(&template, &|name| {
+        let name = name.strip_prefix("r#").unwrap_or(name);
+        let Some(field_name) = field_names().find(|field_name| field_name.unraw() == name) else {
+          let expected = field_names()
+            .map(|field_name| format!("`{}`", field_name.unraw()))
+            .collect::<Vec<_>>()
+            .join(", ");
+          return Err(format!(
+            "unknown field `{}` in the `display` template, expected one of {}",
+            name, expected
+          ));
+        };
+        if attrs.field_flag(field_name, "skip") {
+          return Err(format!(
+            "`{}` is skipped by `#[display(skip)]`, it cannot be formatted",
+            name
+          ));
+        }
+        // The format string is fixed, its arguments cannot come and go
+        if !forwarded_attrs[field_name].cfg().is_empty() {
+          return Err(format!(
+            "`{}` is conditionally compiled through `#[cfg]`, it cannot be used in the `display` template",
+            name
+          ));
+        }
+        match attrs.field_flag(field_name, "secret") {
+          true => Err(format!(
+            "`{}` is marked `#[pod(secret)]`, it cannot be formatted",
+            name
+          )),
+          false => Ok(values[field_name].clone()),
+        }
+      })?;
+      quote! {
+        ::core::write!(f, #format, #(#args),*)
+      }
+    }
+    None => {
+      let separator = attrs
+        .item
+        .lit_str("separator")
+        .map(LitStr::value)
+        .unwrap_or_else(|| " ".to_string());
+      let pairs = orig_fields
+        .named
+        .iter()
+        .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+        .map(|f| {
+          let field_name = f.ident.as_ref().unwrap();
+          let cfg = forwarded_attrs[field_name].cfg();
+          let key = field_name.unraw().to_string();
+          let value = &values[field_name];
+          let has_fmt = attrs.field(field_name).lit_str("fmt").is_some();
+          if attrs.field_flag(field_name, "secret") {
+            return quote! {
+              #cfg
+              {
+                ::core::write!(f, "{}{}=[REDACTED]", separator, #key)?;
+                separator = #separator;
+              }
+            };
+          }
+          // Unset options are left out, as they cannot be displayed
+          match is_option(&f.ty) && !has_fmt {
+            true => quote! {
+              #cfg
+              if let ::core::option::Option::Some(value) = &self.#field_name {
+                ::core::write!(f, "{}{}={}", separator, #key, value)?;
+                separator = #separator;
+              }
+            },
+            false => quote! {
+              #cfg
+              {
+                ::core::write!(f, "{}{}={}", separator, #key, #value)?;
+                separator = #separator;
+              }
+            },
+          }
+        });
+      quote! {
+        let mut separator = "";
+        #(#pairs)*
+        let _ = separator;
+        ::core::result::Result::Ok(())
+      }
+    }
+  };
+
+  let generics = bounded_generics(&input.generics, quote! { ::core::fmt::Display });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics ::core::fmt::Display for #in_name #ty_generics #where_clause {
+      #[allow(unused_assignments)]
+      fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        #body
+      }
+    }
+  })
+}
+
+/// Rewrite the `{field:spec}` placeholders of `template` as positional ones,
+/// along with the value of each, given by `field`.
+fn parse_template(
+  template: &LitStr,
+  field: &dyn Fn(&str) -> Result<TokenStream, String>,
+) -> syn::Result<(String, Vec<TokenStream>)> {
+  let error = |message: String| syn::Error::new_spanned(template, message);
+  let value = template.value();
+  let mut format = String::new();
+  let mut args = vec![];
+  let mut chars = value.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => {
+        chars.next();
+        format.push_str("{{");
+      }
+      '}' if chars.peek() == Some(&'}') => {
+        chars.next();
+        format.push_str("}}");
+      }
+      '}' => {
+        return Err(error(
+          "unmatched `}` in the `display` template, use `}}`".into(),
+        ))
+      }
+      '{' => {
+        let mut placeholder = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => placeholder.push(c),
+            None => {
+              return Err(error(
+                "unmatched `{` in the `display` template, use `{{`".into(),
+              ))
+            }
+          }
+        }
+        let (name, spec) = match placeholder.split_once(':') {
+          Some((name, spec)) => (name.trim(), format!(":{}", spec)),
+          None => (placeholder.trim(), String::new()),
+        };
+        if name.is_empty() {
+          return Err(error(
+            "`display` template placeholders must name a field, e.g. `{name}`".into(),
+          ));
+        }
+        args.push(field(name).map_err(error)?);
+        format.push_str(&format!("{{{}{}}}", args.len() - 1, spec));
+      }
+      c => format.push(c),
+    }
+  }
+  Ok((format, args))
+}
+
+/// A `fmt` formats the field alone, through a single `{}` placeholder.
+fn check_fmt(fmt: &LitStr) -> syn::Result<()> {
+  let value = fmt.value();
+  let placeholders = value.replace("{{", "").matches('{').count();
+  match placeholders {
+    1 => Ok(()),
+    _ => Err(syn::Error::new_spanned(
+      fmt,
+      "`fmt` must hold a single placeholder for the field, e.g. `fmt = \"{:.2}\"`",
+    )),
+  }
+}
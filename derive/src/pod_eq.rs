@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Field, FieldsNamed};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs},
+  utils::{bounded_generics, validate_struct},
+};
+
+/// The fields compared by `PodEq`, left out by `#[eq(skip)]` or by the skip of
+/// the derive given its `attrs`. `PodHash` and `PodOrd` must agree with the
+/// equality, so they leave out the same fields. The shared `#[pod(skip)]` only
+/// hides a field from the accessors, it is still compared.
+pub(crate) fn compared_fields<'a>(
+  input: &DeriveInput,
+  fields: &'a FieldsNamed,
+  attrs: &DeriveAttrs,
+) -> syn::Result<Vec<&'a Field>> {
+  let eq_attrs = DeriveAttrs::parse(input, fields, &attrs::EQ)?;
+  Ok(
+    fields
+      .named
+      .iter()
+      .filter(|f| {
+        let field_name = f.ident.as_ref().unwrap();
+        attrs.field(field_name).flag("skip").is_none()
+          && eq_attrs.field(field_name).flag("skip").is_none()
+      })
+      .collect(),
+  )
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "PodEq")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::EQ)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let comparisons = compared_fields(input, orig_fields, &attrs)?
+    .into_iter()
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      quote! {
+        #cfg
+        if self.#field_name != other.#field_name {
+          return false;
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  let generics = bounded_generics(&input.generics, quote! { ::core::cmp::PartialEq });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let eq = attrs.item.flag("partial").is_none().then(|| {
+    let generics = bounded_generics(&input.generics, quote! { ::core::cmp::Eq });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+      impl #impl_generics ::core::cmp::Eq for #in_name #ty_generics #where_clause {}
+    }
+  });
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics ::core::cmp::PartialEq for #in_name #ty_generics #where_clause {
+      fn eq(&self, other: &Self) -> ::core::primitive::bool {
+        #comparisons
+        true
+      }
+    }
+
+    #eq
+  })
+}
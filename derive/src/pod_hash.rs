@@ -0,0 +1,40 @@
+use proc_macro2::TokenStream;
+use syn::DeriveInput;
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs},
+  pod_eq::compared_fields,
+  utils::{bounded_generics, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "PodHash")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::HASH)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let hashes = compared_fields(input, orig_fields, &attrs)?
+    .into_iter()
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      quote! {
+        #cfg
+        ::core::hash::Hash::hash(&self.#field_name, state);
+      }
+    })
+    .collect::<TokenStream>();
+
+  let generics = bounded_generics(&input.generics, quote! { ::core::hash::Hash });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics ::core::hash::Hash for #in_name #ty_generics #where_clause {
+      fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        #hashes
+      }
+    }
+  })
+}
@@ -0,0 +1,124 @@
+use proc_macro2::TokenStream;
+use syn::{ext::IdentExt, DeriveInput, Expr, ExprLit, Lit};
+
+use crate::{
+  attrs::{self, DeriveAttrs, Errors, ForwardedAttrs},
+  pod_eq::compared_fields,
+  utils::{bounded_generics, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "PodOrd")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::ORD)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let partial = attrs.item.flag("partial").is_some();
+
+  let mut errors = Errors::default();
+  // Skipping a field `PodEq` compares would order unequal values as equal
+  let eq_attrs = DeriveAttrs::parse(input, orig_fields, &attrs::EQ)?;
+  for field_name in orig_fields.named.iter().filter_map(|f| f.ident.as_ref()) {
+    if let Some(skip) = attrs.field(field_name).flag("skip") {
+      if eq_attrs.field(field_name).flag("skip").is_none() {
+        errors.push(syn::Error::new_spanned(
+          skip,
+          format!(
+            "`{}` is still compared for equality, so it cannot be left out of the ordering: add `#[eq(skip)]`",
+            field_name.unraw()
+          ),
+        ));
+      }
+    }
+  }
+  let mut fields = compared_fields(input, orig_fields, &attrs)?
+    .into_iter()
+    .map(|f| {
+      let priority = match attrs.field(f.ident.as_ref().unwrap()).expr("priority") {
+        Some(Expr::Lit(ExprLit {
+          lit: Lit::Int(priority),
+          ..
+        })) => errors.check(priority.base10_parse::<i64>()),
+        Some(priority) => {
+          errors.push(syn::Error::new_spanned(
+            priority,
+            "`priority` must be an integer, e.g. `priority = 1`",
+          ));
+          None
+        }
+        None => None,
+      };
+      (f, priority)
+    })
+    .collect::<Vec<_>>();
+  errors.finish()?;
+  // Prioritized fields come first, the others keep their declaration order
+  fields.sort_by_key(|(_, priority)| (priority.is_none(), *priority));
+
+  let comparisons = fields
+    .into_iter()
+    .map(|(f, _)| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let reverse = attrs.field(field_name).flag("reverse").is_some();
+      match (partial, reverse) {
+        (false, reverse) => {
+          let reverse = reverse.then(|| quote! { .reverse() });
+          quote! {
+            #cfg
+            match ::core::cmp::Ord::cmp(&self.#field_name, &other.#field_name)#reverse {
+              ::core::cmp::Ordering::Equal => {}
+              ordering => return ordering,
+            }
+          }
+        }
+        (true, reverse) => {
+          let reverse =
+            reverse.then(|| quote! { .map(::core::cmp::Ordering::reverse) });
+          quote! {
+            #cfg
+            match ::core::cmp::PartialOrd::partial_cmp(&self.#field_name, &other.#field_name)#reverse {
+              ::core::option::Option::Some(::core::cmp::Ordering::Equal) => {}
+              ordering => return ordering,
+            }
+          }
+        }
+      }
+    })
+    .collect::<TokenStream>();
+
+  Ok(match partial {
+    true => {
+      let generics = bounded_generics(&input.generics, quote! { ::core::cmp::PartialOrd });
+      let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+      quote! {
+        #[allow(deprecated)]
+        impl #impl_generics ::core::cmp::PartialOrd for #in_name #ty_generics #where_clause {
+          fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+            #comparisons
+            ::core::option::Option::Some(::core::cmp::Ordering::Equal)
+          }
+        }
+      }
+    }
+    false => {
+      let generics = bounded_generics(&input.generics, quote! { ::core::cmp::Ord });
+      let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+      quote! {
+        #[allow(deprecated)]
+        impl #impl_generics ::core::cmp::Ord for #in_name #ty_generics #where_clause {
+          fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+            #comparisons
+            ::core::cmp::Ordering::Equal
+          }
+        }
+
+        impl #impl_generics ::core::cmp::PartialOrd for #in_name #ty_generics #where_clause {
+          fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+            ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+          }
+        }
+      }
+    }
+  })
+}
@@ -0,0 +1,133 @@
+use proc_macro2::TokenStream;
+use syn::{
+  ext::IdentExt, punctuated::Punctuated, Attribute, DeriveInput, Expr, ExprLit, Lit, Meta,
+  MetaNameValue,
+};
+
+use crate::{
+  attrs::{self, Args, DeriveAttrs, Errors, ForwardedAttrs},
+  utils::{cfg_slice, pretty, validate_struct},
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Reflect")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::REFLECT)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+  let private = attrs.private();
+
+  let mut errors = Errors::default();
+  let fields = orig_fields
+    .named
+    .iter()
+    .filter(|f| !attrs.field_flag(f.ident.as_ref().unwrap(), "skip"))
+    .map(|f| {
+      let field_name = f.ident.as_ref().unwrap();
+      let cfg = forwarded_attrs[field_name].cfg();
+      let name = field_name.unraw().to_string();
+      let type_name = pretty(&f.ty);
+      let docs = docs(&f.attrs);
+      let vis = pretty(&f.vis);
+      let meta = meta(attrs.field(field_name), &mut errors);
+      let info = quote! {
+        #private::FieldInfo {
+          name: #name,
+          type_name: #type_name,
+          docs: #docs,
+          vis: #vis,
+          meta: &[#(#meta),*],
+        }
+      };
+      (cfg, info)
+    })
+    .collect::<Vec<_>>();
+  errors.finish()?;
+  let fields = cfg_slice(
+    quote! { #private::FieldInfo },
+    quote! {
+      #private::FieldInfo {
+        name: "",
+        type_name: "",
+        docs: "",
+        vis: "",
+        meta: &[],
+      }
+    },
+    fields,
+  );
+
+  let name = in_name.unraw().to_string();
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #impl_generics #private::Reflect for #in_name #ty_generics #where_clause {
+      const NAME: &'static ::core::primitive::str = #name;
+      const FIELDS: &'static [#private::FieldInfo] = #fields;
+    }
+  })
+}
+
+/// The `(key, value)` pairs of `meta(...)`.
+fn meta(args: &Args, errors: &mut Errors) -> Vec<TokenStream> {
+  let mut pairs = vec![];
+  for list in args.lists("meta") {
+    let Some(metas) =
+      errors.check(list.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated))
+    else {
+      continue;
+    };
+    for meta in metas {
+      match (meta.path.get_ident(), &meta.value) {
+        (
+          Some(key),
+          Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+          }),
+        ) => {
+          let key = key.unraw().to_string();
+          pairs.push(quote! { (#key, #value) });
+        }
+        (None, _) => errors.push(syn::Error::new_spanned(
+          &meta.path,
+          "expected a metadata key",
+        )),
+        (Some(_), value) => errors.push(syn::Error::new_spanned(
+          value,
+          "`meta` values must be string literals, e.g. `meta(label = \"Name\")`",
+        )),
+      }
+    }
+  }
+  pairs
+}
+
+/// The `///` docs of a field, one line per doc attribute.
+fn docs(attrs: &[Attribute]) -> TokenStream {
+  let lines = attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("doc"))
+    .filter_map(|attr| match &attr.meta {
+      Meta::NameValue(meta_name_value) => Some(&meta_name_value.value),
+      _ => None,
+    })
+    .map(|value| match value {
+      Expr::Lit(ExprLit {
+        lit: Lit::Str(line),
+        ..
+      }) => {
+        let line = line.value();
+        let line = line.strip_prefix(' ').unwrap_or(&line);
+        quote! { #line }
+      }
+      value => quote! { #value },
+    })
+    .collect::<Vec<_>>();
+  let lines = lines.iter().enumerate().map(|(i, line)| match i {
+    0 => quote! { #line },
+    _ => quote! { "\n", #line },
+  });
+  quote! { ::core::concat!(#(#lines),*) }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, FieldsNamed, Ident, Type, Visibility};
+
+use crate::{
+  attrs::{self, DeriveAttrs, ForwardedAttrs, MethodsVis},
+  names::{check_collisions, MethodNames},
+  tracking::ChangeTracking,
+  utils::{is_option, option_inner, setter_arg, validate_struct},
+};
+
+/// Methods generated on the struct, along with the field they belong to.
+pub(crate) fn methods(input: &DeriveInput) -> syn::Result<Vec<(Ident, Ident)>> {
+  let orig_fields = validate_struct(input, "Setters")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::SETTERS)?;
+  let (tracking, field_skips) = ChangeTracking::with_skips(&attrs, orig_fields, "setters")?;
+  let mut methods = tracking
+    .map(|t| t.method_names(&input.ident))
+    .unwrap_or_default();
+  for f in &orig_fields.named {
+    let field_name = f.ident.as_ref().unwrap();
+    if !field_skips.contains(field_name) {
+      let names = MethodNames::new(attrs.name(field_name), MethodNames::RESERVED);
+      let setters = names.setters(is_option(&f.ty));
+      methods.extend(setters.into_iter().map(|name| (name, field_name.clone())));
+    }
+  }
+  Ok(methods)
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let in_name = &input.ident;
+
+  let orig_fields = validate_struct(input, "Setters")?;
+  let attrs = DeriveAttrs::parse(input, orig_fields, &attrs::SETTERS)?;
+  let methods_vis = MethodsVis::new(&attrs, &input.vis, orig_fields)?;
+  let forwarded_attrs = ForwardedAttrs::new(&attrs, orig_fields)?;
+
+  let (tracking, field_skips) = ChangeTracking::with_skips(&attrs, orig_fields, "setters")?;
+  check_collisions(methods(input)?)?;
+  let with_receiver = WithReceiver::parse(&attrs)?;
+
+  let field_accessors = orig_fields
+    .named
+    .iter()
+    .filter(|f| !field_skips.contains(f.ident.as_ref().unwrap()))
+    .map(|f| {
+      let field_name = f.ident.clone().unwrap();
+      let field_ty = &f.ty;
+      let vis = methods_vis.field(&field_name);
+      let method_attrs = forwarded_attrs[&field_name].method(quote! {});
+      let names = MethodNames::new(attrs.name(&field_name), MethodNames::RESERVED);
+      let MethodNames {
+        get_mut: ref_mut_func_name,
+        set: set_func_name,
+        with: with_func_name,
+        ..
+      } = &names;
+      let mark_dirty = tracking.as_ref().map(|t| t.mark(&field_name));
+      let (arg_ty, arg) = setter_arg(field_ty, attrs.inherited_flag(&field_name, "into"));
+      let with_setter = with_receiver.setter(
+        with_func_name,
+        &field_name,
+        (&arg_ty, &arg),
+        tracking.as_ref(),
+        orig_fields,
+        &forwarded_attrs,
+      );
+      let mutators = mutators(
+        &names,
+        &field_name,
+        field_ty,
+        (&arg_ty, &arg),
+        mark_dirty.as_ref(),
+        vis,
+        |_| method_attrs.clone(),
+      );
+      quote! {
+        #method_attrs
+        #vis fn #ref_mut_func_name(&mut self) -> &mut #field_ty {
+          #mark_dirty
+          &mut self.#field_name
+        }
+
+        #method_attrs
+        #vis fn #set_func_name(&mut self, v: #arg_ty) -> &mut Self {
+          #mark_dirty
+          self.#field_name = #arg;
+          self
+        }
+
+        #method_attrs
+        #vis #with_setter
+
+        #mutators
+      }
+    })
+    .collect::<TokenStream>();
+  let tracking_methods = tracking
+    .as_ref()
+    .map(|t| t.methods(&methods_vis, &forwarded_attrs));
+  let private = attrs.private();
+  let tracking_check = tracking.as_ref().map(ChangeTracking::check);
+  let track_changes = tracking
+    .as_ref()
+    .map(|t| t.impl_track_changes(in_name, &private, &forwarded_attrs));
+
+  Ok(quote! {
+    #[allow(deprecated)]
+    impl #in_name {
+      #field_accessors
+      #tracking_methods
+    }
+
+    #track_changes
+    #tracking_check
+  })
+}
+
+/// How `with_*` setters get hold of the struct they return.
+pub(crate) enum WithReceiver {
+  /// `with_x(mut self, v) -> Self`, consuming it.
+  Owned,
+  /// `with_x(&self, v) -> Self`, cloning the other fields, from `cloning`.
+  Cloning,
+  /// `with_x(self: Arc<Self>, v) -> Arc<Self>`, through `make_mut`, from
+  /// `shared = "Arc"` (or `"Rc"`).
+  Shared(TokenStream),
+}
+
+impl WithReceiver {
+  pub(crate) fn parse(attrs: &DeriveAttrs) -> syn::Result<Self> {
+    let shared = attrs.item.lit_str("shared");
+    match (attrs.item.flag("cloning"), shared) {
+      (Some(cloning), Some(_)) => Err(syn::Error::new_spanned(
+        cloning,
+        "`cloning` and `shared` both generate the `with_*` setters, pick one",
+      )),
+      (Some(_), None) => Ok(Self::Cloning),
+      (None, Some(shared)) => {
+        let private = attrs.private();
+        match shared.value().as_str() {
+          "Arc" => Ok(Self::Shared(quote! { #private::Arc })),
+          "Rc" => Ok(Self::Shared(quote! { #private::Rc })),
+          _ => Err(syn::Error::new_spanned(
+            shared,
+            "`shared` takes the pointer the struct is shared through, `\"Arc\"` or `\"Rc\"`",
+          )),
+        }
+      }
+      (None, None) => Ok(Self::Owned),
+    }
+  }
+
+  /// The `with_*` setter of `field_name`, taking `arg_ty` and assigning `arg`.
+  pub(crate) fn setter(
+    &self,
+    func_name: &Ident,
+    field_name: &Ident,
+    (arg_ty, arg): (&TokenStream, &TokenStream),
+    tracking: Option<&ChangeTracking>,
+    fields: &FieldsNamed,
+    forwarded_attrs: &HashMap<Ident, ForwardedAttrs>,
+  ) -> TokenStream {
+    match self {
+      Self::Owned => {
+        let mark_dirty = tracking.map(|t| t.mark(field_name));
+        quote! {
+          fn #func_name(mut self, v: #arg_ty) -> Self {
+            #mark_dirty
+            self.#field_name = #arg;
+            self
+          }
+        }
+      }
+      Self::Cloning => {
+        let mark_dirty = tracking.map(|t| t.mark_on(quote! { copy }, field_name));
+        let others = fields
+          .named
+          .iter()
+          .filter_map(|f| f.ident.as_ref())
+          .filter(|other| *other != field_name)
+          .map(|other| {
+            let cfg = forwarded_attrs[other].cfg();
+            quote! {
+              #cfg
+              #other: ::core::clone::Clone::clone(&self.#other),
+            }
+          });
+        quote! {
+          fn #func_name(&self, v: #arg_ty) -> Self {
+            #[allow(unused_mut)]
+            let mut copy = Self {
+              #field_name: #arg,
+              #(#others)*
+            };
+            #mark_dirty
+            copy
+          }
+        }
+      }
+      Self::Shared(pointer) => {
+        let mark_dirty = tracking.map(|t| t.mark_on(quote! { this }, field_name));
+        quote! {
+          fn #func_name(mut self: #pointer<Self>, v: #arg_ty) -> #pointer<Self> {
+            let this = #pointer::make_mut(&mut self);
+            #mark_dirty
+            this.#field_name = #arg;
+            self
+          }
+        }
+      }
+    }
+  }
+}
+
+/// The `update_*` and `replace_*` setters of a field, plus `take_*` and
+/// `get_or_insert_*_with` for `Option` ones. `method_attrs` gives the
+/// attributes of each method from its default doc.
+pub(crate) fn mutators(
+  names: &MethodNames,
+  field_name: &Ident,
+  field_ty: &Type,
+  (arg_ty, arg): (&TokenStream, &TokenStream),
+  mark_dirty: Option<&TokenStream>,
+  vis: &Visibility,
+  method_attrs: impl Fn(TokenStream) -> TokenStream,
+) -> TokenStream {
+  let MethodNames {
+    update,
+    replace,
+    take,
+    get_or_insert_with,
+    ..
+  } = names;
+  let update_attrs = method_attrs(quote! {
+    #[doc = ::core::concat!("Update the `", ::core::stringify!(#field_name), "` field in place.")]
+  });
+  let replace_attrs = method_attrs(quote! {
+    #[doc = ::core::concat!("Define the `", ::core::stringify!(#field_name), "` field, returning its previous value.")]
+  });
+  let optional = option_inner(field_ty).map(|inner_ty| {
+    let take_attrs = method_attrs(quote! {
+      #[doc = ::core::concat!("Take the `", ::core::stringify!(#field_name), "` field, leaving `None` in its place.")]
+    });
+    let get_or_insert_attrs = method_attrs(quote! {
+      #[doc = ::core::concat!("Return the `", ::core::stringify!(#field_name), "` field as a mutable reference, inserting the value of `f` if unset.")]
+    });
+    quote! {
+      #take_attrs
+      #vis fn #take(&mut self) -> #field_ty {
+        #mark_dirty
+        ::core::option::Option::take(&mut self.#field_name)
+      }
+
+      #get_or_insert_attrs
+      #vis fn #get_or_insert_with(&mut self, f: impl ::core::ops::FnOnce() -> #inner_ty) -> &mut #inner_ty {
+        #mark_dirty
+        ::core::option::Option::get_or_insert_with(&mut self.#field_name, f)
+      }
+    }
+  });
+  quote! {
+    #update_attrs
+    #vis fn #update(&mut self, f: impl ::core::ops::FnOnce(&mut #field_ty)) -> &mut Self {
+      #mark_dirty
+      f(&mut self.#field_name);
+      self
+    }
+
+    #replace_attrs
+    #vis fn #replace(&mut self, v: #arg_ty) -> #field_ty {
+      #mark_dirty
+      ::core::mem::replace(&mut self.#field_name, #arg)
+    }
+
+    #optional
+  }
+}
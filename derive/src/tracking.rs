@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Literal, TokenStream};
+use syn::{ext::IdentExt, spanned::Spanned, FieldsNamed, Ident, Path, Type};
+
+use crate::attrs::{DeriveAttrs, ForwardedAttrs, MethodsVis};
+
+/// Dirty tracking requested through `track_changes`: the field storing the
+/// bitset, and the tracked fields in bit order.
+pub(crate) struct ChangeTracking {
+  flags: Ident,
+  flags_ty: Type,
+  /// The tracked fields, along with the base name of their methods.
+  fields: Vec<(Ident, Ident)>,
+}
+
+impl ChangeTracking {
+  /// Look for `#[<attr>(track_changes)]` on the struct and the matching
+  /// `#[<attr>(dirty_flags)]` field.
+  fn new(
+    attrs: &DeriveAttrs,
+    fields: &FieldsNamed,
+    attr: &str,
+    skips: &[Ident],
+  ) -> syn::Result<Option<Self>> {
+    let track_changes = attrs.item.flag("track_changes");
+    let mut flags: Option<(Ident, Type, &Path)> = None;
+    for field in &fields.named {
+      let field_name = field.ident.as_ref().unwrap();
+      if let Some(path) = attrs.field(field_name).flag("dirty_flags") {
+        if flags.is_some() {
+          return Err(syn::Error::new(
+            path.span(),
+            "`dirty_flags` can only be set on a single field",
+          ));
+        }
+        flags = Some((field_name.clone(), field.ty.clone(), path));
+      }
+    }
+    match (track_changes, flags) {
+      (None, None) => Ok(None),
+      (Some(path), None) => Err(syn::Error::new(
+        path.span(),
+        format!(
+          "`track_changes` requires a field marked with `#[{}(dirty_flags)]`",
+          attr
+        ),
+      )),
+      (None, Some((_, _, path))) => Err(syn::Error::new(
+        path.span(),
+        format!(
+          "`dirty_flags` requires `#[{}(track_changes)]` on the struct",
+          attr
+        ),
+      )),
+      (Some(_), Some((flags, flags_ty, _))) => {
+        let fields = fields
+          .named
+          .iter()
+          .filter_map(|f| f.ident.clone())
+          .filter(|f| *f != flags && !skips.contains(f))
+          .map(|f| {
+            let name = attrs.name(&f).clone();
+            (f, name)
+          })
+          .collect();
+        Ok(Some(Self {
+          flags,
+          flags_ty,
+          fields,
+        }))
+      }
+    }
+  }
+
+  /// The change tracking of `attr`, along with the fields which get no
+  /// accessor: the skipped ones and the `dirty_flags` one.
+  pub(crate) fn with_skips(
+    attrs: &DeriveAttrs,
+    fields: &FieldsNamed,
+    attr: &str,
+  ) -> syn::Result<(Option<Self>, Vec<Ident>)> {
+    let mut skips: Vec<Ident> = fields
+      .named
+      .iter()
+      .filter_map(|f| f.ident.clone())
+      .filter(|field_name| attrs.field_flag(field_name, "skip"))
+      .collect();
+    let tracking = Self::new(attrs, fields, attr, &skips)?;
+    if let Some(tracking) = &tracking {
+      skips.push(tracking.flags.clone());
+    }
+    Ok((tracking, skips))
+  }
+
+  fn bit(&self, field: &Ident) -> Option<Literal> {
+    self
+      .fields
+      .iter()
+      .position(|(f, _)| f == field)
+      .map(Literal::usize_unsuffixed)
+  }
+
+  fn is_dirty_method(field: &Ident) -> Ident {
+    Ident::new(&format!("is_{}_dirty", field.unraw()), field.span())
+  }
+
+  /// Names of the generated methods, along with the field they belong to.
+  pub(crate) fn method_names(&self, item: &Ident) -> Vec<(Ident, Ident)> {
+    let mut names = vec![
+      (Ident::new("dirty_fields", item.span()), item.clone()),
+      (Ident::new("clear_dirty", item.span()), item.clone()),
+    ];
+    names.extend(
+      self
+        .fields
+        .iter()
+        .map(|(f, name)| (Self::is_dirty_method(name), f.clone())),
+    );
+    names
+  }
+
+  /// Statement flagging `field` as dirty.
+  pub(crate) fn mark(&self, field: &Ident) -> TokenStream {
+    self.mark_on(quote! { self }, field)
+  }
+
+  /// Statement flagging `field` of `receiver`, another instance, as dirty.
+  pub(crate) fn mark_on(&self, receiver: TokenStream, field: &Ident) -> TokenStream {
+    let flags = &self.flags;
+    match self.bit(field) {
+      Some(bit) => quote! { #receiver.#flags |= 1 << #bit; },
+      None => quote! {},
+    }
+  }
+
+  /// The `is_<field>_dirty`, `dirty_fields` and `clear_dirty` methods.
+  pub(crate) fn methods(
+    &self,
+    methods_vis: &MethodsVis,
+    forwarded_attrs: &HashMap<Ident, ForwardedAttrs>,
+  ) -> TokenStream {
+    let flags = &self.flags;
+    let vis = &methods_vis.item;
+    let is_dirty = self
+      .fields
+      .iter()
+      .map(|(field_name, name)| {
+        let is_dirty_func_name = Self::is_dirty_method(name);
+        let bit = self.bit(field_name);
+        let vis = methods_vis.field(field_name);
+        let attrs = forwarded_attrs[field_name].method(quote! {
+          #[doc = ::core::concat!("Check whether the `", ::core::stringify!(#field_name), "` field was modified since the last `clear_dirty`.")]
+        });
+        quote! {
+          #attrs
+          #vis fn #is_dirty_func_name(&self) -> ::core::primitive::bool {
+            (self.#flags >> #bit) & 1 != 0
+          }
+        }
+      })
+      .collect::<TokenStream>();
+    let count = self.fields.len();
+    let collect_dirty = self
+      .fields
+      .iter()
+      .map(|(field_name, _)| {
+        let cfg = forwarded_attrs[field_name].cfg();
+        let name = field_name.unraw().to_string();
+        let bit = self.bit(field_name);
+        quote! {
+          #cfg
+          if (self.#flags >> #bit) & 1 != 0 {
+            dirty[#bit] = ::core::option::Option::Some(#name);
+          }
+        }
+      })
+      .collect::<TokenStream>();
+    quote! {
+      #is_dirty
+
+      /// Iterate over the names of the fields modified since the last `clear_dirty`.
+      #vis fn dirty_fields(&self) -> impl ::core::iter::Iterator<Item = &'static ::core::primitive::str> {
+        let mut dirty: [::core::option::Option<&'static ::core::primitive::str>; #count] =
+          [::core::option::Option::None; #count];
+        #collect_dirty
+        ::core::iter::Iterator::flatten(::core::iter::IntoIterator::into_iter(dirty))
+      }
+
+      /// Mark every field as clean.
+      #vis fn clear_dirty(&mut self) {
+        self.#flags = 0;
+      }
+    }
+  }
+
+  /// `TrackChanges`, naming the fields as `dirty_fields` does.
+  pub(crate) fn impl_track_changes(
+    &self,
+    in_name: &Ident,
+    private: &TokenStream,
+    forwarded_attrs: &HashMap<Ident, ForwardedAttrs>,
+  ) -> TokenStream {
+    let flags = &self.flags;
+    let is_dirty = self
+      .fields
+      .iter()
+      .map(|(field_name, _)| {
+        let cfg = forwarded_attrs[field_name].cfg();
+        let name = field_name.unraw().to_string();
+        let bit = self.bit(field_name);
+        quote! {
+          #cfg
+          #name => (self.#flags >> #bit) & 1 != 0,
+        }
+      })
+      .collect::<TokenStream>();
+    quote! {
+      #[allow(deprecated)]
+      impl #private::TrackChanges for #in_name {
+        fn is_field_dirty(&self, name: &::core::primitive::str) -> ::core::primitive::bool {
+          match name {
+            #is_dirty
+            _ => false,
+          }
+        }
+      }
+    }
+  }
+
+  /// Compile-time check that the bitset is wide enough for every tracked field.
+  pub(crate) fn check(&self) -> TokenStream {
+    let flags_ty = &self.flags_ty;
+    let count = self.fields.len();
+    quote! {
+      const _: () = ::core::assert!(
+        #count <= <#flags_ty>::BITS as ::core::primitive::usize,
+        "`dirty_flags` field is too narrow to track every field"
+      );
+    }
+  }
+}
@@ -0,0 +1,160 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+  ext::IdentExt, spanned::Spanned, Data, DeriveInput, Fields, FieldsNamed, GenericArgument,
+  GenericParam, Generics, Ident, PathArguments, Type,
+};
+
+pub(crate) fn is_option(ty: &Type) -> bool {
+  match ty {
+    Type::Path(path) if path.qself.is_none() => {
+      path.path.segments.iter().any(|seg| seg.ident == "Option")
+    }
+    _ => false,
+  }
+}
+
+/// The `T` of an `Option<T>` type.
+pub(crate) fn option_inner(ty: &Type) -> Option<&GenericArgument> {
+  match ty {
+    Type::Path(path) if is_option(ty) => {
+      path
+        .path
+        .segments
+        .iter()
+        .find_map(|seg| match &seg.arguments {
+          PathArguments::AngleBracketed(args) => args.args.first(),
+          _ => None,
+        })
+    }
+    _ => None,
+  }
+}
+
+/// The enum variant naming a field: `display_name` gives `DisplayName`.
+pub(crate) fn variant_name(field_name: &Ident) -> Ident {
+  let name = field_name.unraw().to_string().to_case(Case::Pascal);
+  Ident::new(&name, field_name.span())
+}
+
+/// The named fields of the derived struct.
+pub(crate) fn validate_struct<'a>(
+  input: &'a DeriveInput,
+  derive: &str,
+) -> syn::Result<&'a FieldsNamed> {
+  match &input.data {
+    Data::Struct(s) => match &s.fields {
+      Fields::Named(fields) => Ok(fields),
+      Fields::Unit => Err(syn::Error::new(
+        s.struct_token.span(),
+        format!("`{}` derive macro only available for named fields", derive),
+      )),
+      Fields::Unnamed(u) => Err(syn::Error::new(
+        u.paren_token.span.join(),
+        format!("`{}` derive macro only available for named fields", derive),
+      )),
+    },
+    Data::Enum(e) => Err(syn::Error::new(
+      e.enum_token.span(),
+      format!("`{}` derive macro only available for Struct", derive),
+    )),
+    Data::Union(u) => Err(syn::Error::new(
+      u.union_token.span(),
+      format!("`{}` derive macro only available for Struct", derive),
+    )),
+  }
+}
+
+/// Parameter type and stored value of a setter taking `v`, which accepts
+/// anything convertible to `ty` when `into` is set.
+pub(crate) fn setter_arg(ty: impl ToTokens, into: bool) -> (TokenStream, TokenStream) {
+  match into {
+    true => (
+      quote! { impl ::core::convert::Into<#ty> },
+      quote! { ::core::convert::Into::into(v) },
+    ),
+    false => (quote! { #ty }, quote! { v }),
+  }
+}
+
+/// Render `tokens` the way they are usually written, e.g. `Option<u32>`
+/// rather than `Option < u32 >`.
+pub(crate) fn pretty(tokens: impl ToTokens) -> String {
+  let mut pretty = tokens.to_token_stream().to_string();
+  for (from, to) in [
+    (" :: ", "::"),
+    (":: ", "::"),
+    (" <", "<"),
+    ("< ", "<"),
+    (" >", ">"),
+    (" ,", ","),
+    (" ;", ";"),
+    ("& ", "&"),
+    ("( ", "("),
+    (" )", ")"),
+    ("pub (", "pub("),
+  ] {
+    pretty = pretty.replace(from, to);
+  }
+  pretty
+}
+
+/// A `&'static [ty]` const expression holding `items`, each compiled out by
+/// its `#[cfg]` attributes. Array literals reject attributes on their
+/// elements, so the slice is filled in a const block instead, starting from
+/// copies of `placeholder`.
+pub(crate) fn cfg_slice(
+  ty: TokenStream,
+  placeholder: TokenStream,
+  items: Vec<(TokenStream, TokenStream)>,
+) -> TokenStream {
+  let (cfgs, values): (Vec<_>, Vec<_>) = items.into_iter().unzip();
+  quote! {
+    {
+      const COUNT: ::core::primitive::usize = {
+        let mut count = 0;
+        #(
+          #cfgs
+          {
+            count += 1;
+          }
+        )*
+        count
+      };
+      #[allow(unused_assignments)]
+      const ITEMS: [#ty; COUNT] = {
+        let mut items = [#placeholder; COUNT];
+        let mut i = 0;
+        #(
+          #cfgs
+          {
+            items[i] = #values;
+            i += 1;
+          }
+        )*
+        items
+      };
+      &ITEMS
+    }
+  }
+}
+
+/// The generics of the struct, with `bound` required of every type parameter
+/// as the standard derives do, e.g. `T: Debug`.
+pub(crate) fn bounded_generics(generics: &Generics, bound: TokenStream) -> Generics {
+  let mut generics = generics.clone();
+  let type_params = generics
+    .params
+    .iter()
+    .filter_map(|param| match param {
+      GenericParam::Type(param) => Some(param.ident.clone()),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  let where_clause = generics.make_where_clause();
+  for param in type_params {
+    where_clause.predicates.push(parse_quote!(#param: #bound));
+  }
+  generics
+}
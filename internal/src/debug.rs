@@ -0,0 +1,36 @@
+use core::fmt;
+
+/// A value kept out of `Debug` output, returned by the getters of
+/// `#[pod(secret)]` fields. The value is only reachable via [`Secret::expose`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+  pub const fn new(value: T) -> Self {
+    Self(value)
+  }
+
+  /// The wrapped value, to be kept out of logs.
+  pub fn expose(self) -> T {
+    self.0
+  }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[REDACTED]")
+  }
+}
+
+/// Formats a field with the function given by `#[debug(with = ...)]`.
+#[doc(hidden)]
+pub struct DebugWith<'a, T: ?Sized>(
+  pub &'a T,
+  pub fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+);
+
+impl<T: ?Sized> fmt::Debug for DebugWith<'_, T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    (self.1)(self.0, f)
+  }
+}
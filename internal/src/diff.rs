@@ -0,0 +1,13 @@
+use alloc::vec::Vec;
+
+/// Field-level comparison of two values, derived by `Diff`.
+pub trait Diff {
+  /// One change to a single field, generated as `<Struct>Change`.
+  type Change;
+
+  /// The changes turning `self` into `other`, in field order.
+  fn diff(&self, other: &Self) -> Vec<Self::Change>;
+
+  /// Replay `changes`, as returned by [`Diff::diff`], onto `self`.
+  fn apply_changes(&mut self, changes: &[Self::Change]);
+}
@@ -0,0 +1,141 @@
+use core::{any::Any, fmt, marker::PhantomData, str::FromStr};
+
+use crate::FieldNames;
+
+/// Access to the fields of a struct by name, derived by `Dynamic`.
+pub trait Dynamic {
+  /// The field named `name`, if any.
+  fn get_field(&self, name: &str) -> Option<&dyn Any>;
+
+  /// The field named `name` as a mutable reference, if any.
+  fn get_field_mut(&mut self, name: &str) -> Option<&mut dyn Any>;
+
+  /// Parse `value` through `FromStr` and store it into the field named
+  /// `name`. An empty `value` resets `Option` fields to `None`.
+  fn set_field_str(&mut self, name: &str, value: &str) -> Result<(), FieldError>;
+
+  /// `get_field`, keyed by the `FieldNames` enum.
+  fn get_key(&self, key: <Self as FieldNames>::Field) -> Option<&dyn Any>
+  where
+    Self: FieldNames + Sized,
+  {
+    self.get_field(key.into())
+  }
+
+  /// `get_field_mut`, keyed by the `FieldNames` enum.
+  fn get_key_mut(&mut self, key: <Self as FieldNames>::Field) -> Option<&mut dyn Any>
+  where
+    Self: FieldNames + Sized,
+  {
+    self.get_field_mut(key.into())
+  }
+
+  /// `set_field_str`, keyed by the `FieldNames` enum.
+  fn set_key_str(&mut self, key: <Self as FieldNames>::Field, value: &str) -> Result<(), FieldError>
+  where
+    Self: FieldNames + Sized,
+  {
+    self.set_field_str(key.into(), value)
+  }
+}
+
+/// Error returned by `Dynamic::set_field_str`.
+///
+/// The variants holding messages only do so with the `alloc` feature, so they
+/// are matched with `..` whichever features are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+  /// No field has this name.
+  #[non_exhaustive]
+  Unknown {
+    /// The rejected name.
+    #[cfg(feature = "alloc")]
+    name: alloc::string::String,
+  },
+  /// The field is marked `#[dynamic(read_only)]`.
+  ReadOnly(&'static str),
+  /// The field type does not implement `FromStr`.
+  Unsupported(&'static str),
+  /// The value could not be parsed into the field type.
+  #[non_exhaustive]
+  Parse {
+    field: &'static str,
+    /// The `FromStr` error message.
+    #[cfg(feature = "alloc")]
+    reason: alloc::string::String,
+  },
+}
+
+impl fmt::Display for FieldError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      #[cfg(feature = "alloc")]
+      Self::Unknown { name } => write!(f, "unknown field `{}`", name),
+      #[cfg(not(feature = "alloc"))]
+      Self::Unknown {} => write!(f, "unknown field"),
+      Self::ReadOnly(field) => write!(f, "field `{}` is read-only", field),
+      Self::Unsupported(field) => write!(f, "field `{}` cannot be parsed from a string", field),
+      #[cfg(feature = "alloc")]
+      Self::Parse { field, reason } => write!(f, "invalid value for field `{}`: {}", field, reason),
+      #[cfg(not(feature = "alloc"))]
+      Self::Parse { field } => write!(f, "invalid value for field `{}`", field),
+    }
+  }
+}
+
+impl FieldError {
+  /// `Unknown`, keeping `name` when allocations are available.
+  #[doc(hidden)]
+  pub fn unknown(_name: &str) -> Self {
+    Self::Unknown {
+      #[cfg(feature = "alloc")]
+      name: alloc::string::ToString::to_string(_name),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FieldError {}
+
+/// Parses the value of a field through `FromStr` when its type implements it,
+/// falling back to `FieldError::Unsupported` otherwise: `ParseFromStr` applies
+/// to `ParseField<T>` and `ParseUnsupported` to `&ParseField<T>`, so method
+/// resolution on `(&ParseField::<T>::new(..)).parse_field(..)` picks the former
+/// whenever it can.
+#[doc(hidden)]
+pub struct ParseField<T>(&'static str, PhantomData<T>);
+
+impl<T> ParseField<T> {
+  pub const fn new(field: &'static str) -> Self {
+    Self(field, PhantomData)
+  }
+}
+
+#[doc(hidden)]
+pub trait ParseFromStr<T> {
+  fn parse_field(&self, value: &str) -> Result<T, FieldError>;
+}
+
+impl<T: FromStr> ParseFromStr<T> for ParseField<T>
+where
+  T::Err: fmt::Display,
+{
+  fn parse_field(&self, value: &str) -> Result<T, FieldError> {
+    value.parse().map_err(|_error: T::Err| FieldError::Parse {
+      field: self.0,
+      #[cfg(feature = "alloc")]
+      reason: alloc::string::ToString::to_string(&_error),
+    })
+  }
+}
+
+#[doc(hidden)]
+pub trait ParseUnsupported<T> {
+  fn parse_field(&self, value: &str) -> Result<T, FieldError>;
+}
+
+impl<T> ParseUnsupported<T> for &ParseField<T> {
+  fn parse_field(&self, _value: &str) -> Result<T, FieldError> {
+    Err(FieldError::Unsupported(self.0))
+  }
+}
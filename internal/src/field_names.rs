@@ -0,0 +1,32 @@
+/// Names the fields of a struct through its `<Struct>Field` enum, derived by
+/// `FieldNames`.
+///
+/// The enum keys the fields for `Dynamic` and `TrackChanges`, in place of
+/// their `&str` names.
+pub trait FieldNames {
+  /// The `<Struct>Field` enum.
+  type Field: Copy + Into<&'static str> + 'static;
+
+  /// Every field, in declaration order.
+  const KEYS: &'static [Self::Field];
+}
+
+/// Dirty tracking of the fields, implemented by `Setters` and `Fields` along
+/// with `track_changes`.
+pub trait TrackChanges {
+  /// Whether the field named `name` was modified since the last
+  /// `clear_dirty`.
+  fn is_field_dirty(&self, name: &str) -> bool;
+
+  /// The fields modified since the last `clear_dirty`, keyed by their
+  /// `FieldNames` enum.
+  fn dirty_keys(&self) -> impl Iterator<Item = <Self as FieldNames>::Field> + '_
+  where
+    Self: FieldNames + Sized,
+  {
+    Self::KEYS
+      .iter()
+      .copied()
+      .filter(|key| self.is_field_dirty((*key).into()))
+  }
+}
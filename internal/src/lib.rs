@@ -1,3 +1,33 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod debug;
+#[cfg(feature = "alloc")]
+mod diff;
+mod dynamic;
+mod field_names;
+mod merge;
+mod reflect;
+
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub use alloc::{rc::Rc, sync::Arc, vec::Vec};
+#[doc(hidden)]
+pub use debug::DebugWith;
+pub use debug::Secret;
+#[cfg(feature = "alloc")]
+pub use diff::Diff;
+pub use dynamic::{Dynamic, FieldError};
+#[doc(hidden)]
+pub use dynamic::{ParseField, ParseFromStr, ParseUnsupported};
+pub use field_names::{FieldNames, TrackChanges};
+pub use merge::Merge;
+pub use reflect::{FieldInfo, Reflect};
+
 pub trait Builder {
   type Target;
 
@@ -0,0 +1,16 @@
+/// Deep merge of two values, derived by `Merge`.
+pub trait Merge {
+  /// Merge `other` into `self`, field by field.
+  fn merge(&mut self, other: Self);
+}
+
+/// Merges both sides when they are set, and otherwise keeps whichever is.
+impl<T: Merge> Merge for Option<T> {
+  fn merge(&mut self, other: Self) {
+    match (self.as_mut(), other) {
+      (Some(value), Some(other)) => value.merge(other),
+      (None, Some(other)) => *self = Some(other),
+      (_, None) => {}
+    }
+  }
+}
@@ -0,0 +1,38 @@
+/// Static description of a struct and its fields, derived by `Reflect`.
+pub trait Reflect {
+  /// Name of the struct.
+  const NAME: &'static str;
+  /// The fields, in declaration order, without the skipped ones.
+  const FIELDS: &'static [FieldInfo];
+
+  /// Find a field by name.
+  fn field_info(name: &str) -> Option<&'static FieldInfo> {
+    Self::FIELDS.iter().find(|field| field.name == name)
+  }
+}
+
+/// Description of a single field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+  /// Name of the field, without the `r#` prefix of raw identifiers.
+  pub name: &'static str,
+  /// The type, as written in the struct.
+  pub type_name: &'static str,
+  /// The `///` docs, one line per doc attribute.
+  pub docs: &'static str,
+  /// The visibility, as written in the struct: `""` for private fields.
+  pub vis: &'static str,
+  /// Pairs given by `#[reflect(meta(key = "value"))]`.
+  pub meta: &'static [(&'static str, &'static str)],
+}
+
+impl FieldInfo {
+  /// The metadata value of `key`, if any.
+  pub fn meta(&self, key: &str) -> Option<&'static str> {
+    self
+      .meta
+      .iter()
+      .find(|(k, _)| *k == key)
+      .map(|(_, value)| *value)
+  }
+}
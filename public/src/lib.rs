@@ -1,3 +1,11 @@
+#![no_std]
+
 #[cfg(feature = "derive")]
 pub use podstru_derive::*;
 pub use podstru_internal::*;
+
+/// Items the generated code relies on, not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+  pub use podstru_internal::*;
+}